@@ -4,11 +4,11 @@ use libpafe::types::{AccessMode, BlockData, BlockElement, Idm, ServiceCode};
 
 fn bench_encode_write_multi(c: &mut Criterion) {
     let mut group = c.benchmark_group("encode_write_multi");
-    for &blocks in &[1usize, 8usize, 32usize] {
+    for &blocks in &[1usize, 8usize, 11usize] {
         let idm = Idm::from_bytes([0x01; 8]);
         let services = vec![ServiceCode::new(0x118b)];
         let blocks_vec: Vec<BlockElement> = (0..blocks)
-            .map(|i| BlockElement::new(0, AccessMode::DirectAccessOrRead, i as u16))
+            .map(|i| BlockElement::new(0, AccessMode::Normal, i as u16))
             .collect();
         let data: Vec<BlockData> = (0..blocks)
             .map(|_| BlockData::from_bytes([0u8; 16]))
@@ -22,7 +22,7 @@ fn bench_encode_write_multi(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::from_parameter(blocks), &cmd, |b, cmd| {
             b.iter(|| {
-                black_box(cmd.encode());
+                black_box(cmd.encode().unwrap());
             });
         });
     }
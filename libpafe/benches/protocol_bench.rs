@@ -28,7 +28,7 @@ fn bench_command_encode(c: &mut Criterion) {
     };
     group.bench_function("polling_encode", |b| {
         b.iter(|| {
-            black_box(polling.encode());
+            black_box(polling.encode().unwrap());
         })
     });
 
@@ -36,7 +36,7 @@ fn bench_command_encode(c: &mut Criterion) {
     let idm = Idm::from_bytes([0u8; 8]);
     let services: Vec<ServiceCode> = (0..4).map(|i| ServiceCode::new(0x090f + i)).collect();
     let blocks: Vec<BlockElement> = (0..8)
-        .map(|i| BlockElement::new(0, AccessMode::DirectAccessOrRead, i as u16))
+        .map(|i| BlockElement::new(0, AccessMode::Normal, i as u16))
         .collect();
     let read_cmd = Command::ReadWithoutEncryption {
         idm,
@@ -45,7 +45,7 @@ fn bench_command_encode(c: &mut Criterion) {
     };
     group.bench_function("read_encode_8blocks", |b| {
         b.iter(|| {
-            black_box(read_cmd.encode());
+            black_box(read_cmd.encode().unwrap());
         })
     });
 
@@ -3,12 +3,19 @@
 use crate::Result;
 use crate::device::Device;
 use crate::types::{
-    Atqb, BlockData, BlockElement, CardType, Idm, Pmm, ServiceCode, SystemCode, Uid,
+    Atqb, BlockData, BlockElement, CardType, ChipKind, Idm, Pmm, PollingRequest,
+    PollingRequestData, ServiceCode, SystemCode, Uid,
 };
 
 mod info;
 pub use info::CardInfo;
 
+mod session;
+pub use session::CardSession;
+
+mod tree;
+pub use tree::{AreaNode, ServiceNode};
+
 pub mod builder;
 pub mod operations;
 
@@ -46,6 +53,25 @@ impl Card {
         }
     }
 
+    /// Create a FeliCa card from a Polling response.
+    ///
+    /// The card takes the system code reported in the Request Data when
+    /// `request` asked for it, and the polled `system_code` otherwise.
+    pub(crate) fn from_polling(
+        idm: Idm,
+        pmm: Pmm,
+        system_code: SystemCode,
+        request: PollingRequest,
+        request_data: Option<[u8; 2]>,
+    ) -> (Self, Option<PollingRequestData>) {
+        let data = request_data.and_then(|raw| request.decode_request_data(raw));
+        let system_code = match data {
+            Some(PollingRequestData::SystemCode(sc)) => sc,
+            _ => system_code,
+        };
+        (Self::new(idm, pmm, system_code), data)
+    }
+
     /// Create a new Type A card
     pub fn new_type_a(uid: Uid) -> Self {
         Self::TypeA { uid }
@@ -81,6 +107,11 @@ impl Card {
         }
     }
 
+    /// Chip family decoded from the PMm (FeliCa only, returns None for Type A/B)
+    pub fn chip_kind(&self) -> Option<ChipKind> {
+        self.pmm().map(Pmm::chip_kind)
+    }
+
     /// Get System Code (FeliCa only, returns None for Type A/B)
     pub fn system_code(&self) -> Option<SystemCode> {
         match self {
@@ -148,7 +179,7 @@ impl Card {
                 "write_single is only supported for FeliCa (Type F) cards".into(),
             ));
         }
-        let blk = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, block);
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, block);
         operations::write::write_single(self, device, service, blk, data)
     }
 
@@ -167,6 +198,74 @@ impl Card {
         operations::write::write_blocks(self, device, service, blocks)
     }
 
+    /// Write a single block with optional readback verification and
+    /// retries (FeliCa/Type F only)
+    pub fn write_single_with_options(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        service: ServiceCode,
+        block: u16,
+        data: BlockData,
+        options: operations::WriteOptions,
+    ) -> Result<operations::WriteReport> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "write_single_with_options is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, block);
+        operations::write_blocks_with_options(self, device, service, &[(blk, data)], options)
+    }
+
+    /// Write multiple blocks with optional readback verification and
+    /// retries (FeliCa/Type F only)
+    pub fn write_blocks_with_options(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        service: ServiceCode,
+        blocks: &[(BlockElement, BlockData)],
+        options: operations::WriteOptions,
+    ) -> Result<operations::WriteReport> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "write_blocks_with_options is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::write_blocks_with_options(self, device, service, blocks, options)
+    }
+
+    /// Write blocks of `service` as one tear-safe transaction staged in
+    /// `journal` (FeliCa/Type F only)
+    pub fn write_journaled(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        journal: &operations::Journal,
+        service: ServiceCode,
+        blocks: &[(u16, BlockData)],
+    ) -> Result<()> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "write_journaled is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        journal.write(self, device, service, blocks)
+    }
+
+    /// Finish or roll back a journaled write interrupted on this card
+    /// (FeliCa/Type F only)
+    pub fn recover(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        journal: &operations::Journal,
+    ) -> Result<operations::RecoveryOutcome> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "recover is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        journal.recover(self, device)
+    }
+
     /// Return an iterator over service codes found by SearchServiceCode (FeliCa/Type F only)
     pub fn services<'a>(
         &'a self,
@@ -175,6 +274,49 @@ impl Card {
         operations::ServiceIterator::new(self, device)
     }
 
+    /// Walk every node with SearchServiceCode and return the card's
+    /// area/service hierarchy (FeliCa/Type F only)
+    pub fn discover_tree(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+    ) -> Result<AreaNode> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "discover_tree is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::discover_tree(self, device)
+    }
+
+    /// Read and decode the block of a purse service (FeliCa only)
+    pub fn read_purse(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        service: ServiceCode,
+    ) -> Result<operations::PurseBlock> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "read_purse is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::read_purse(self, device, service)
+    }
+
+    /// Read the latest `count` records of a cyclic service, oldest first (FeliCa only)
+    pub fn read_cyclic_latest(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        service: ServiceCode,
+        count: usize,
+    ) -> Result<Vec<BlockData>> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "read_cyclic_latest is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::read_cyclic_latest(self, device, service, count)
+    }
+
     /// Request service/node key versions for the provided codes (FeliCa only)
     pub fn request_service_versions(
         &self,
@@ -214,6 +356,64 @@ impl Card {
         }
         operations::request_system_codes(self, device)
     }
+
+    /// Request block counts for the provided node codes via
+    /// RequestBlockInformation (FeliCa only)
+    pub fn request_block_information(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        node_codes: &[u16],
+    ) -> Result<Vec<u16>> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "request_block_information is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::request_block_information(self, device, node_codes)
+    }
+
+    /// Request assigned/free block counts for the provided node codes via
+    /// RequestBlockInformationEx (FeliCa only)
+    pub fn request_block_information_ex(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        node_codes: &[u16],
+    ) -> Result<Vec<crate::protocol::BlockInformation>> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "request_block_information_ex is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::request_block_information_ex(self, device, node_codes)
+    }
+
+    /// Read every block of a service, querying the block count first (FeliCa only)
+    pub fn read_service_all(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        service: ServiceCode,
+    ) -> Result<Vec<BlockData>> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "read_service_all is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        operations::read_service_all(self, device, service)
+    }
+
+    /// Read the blocks of a multi-service `ReadPlan` (FeliCa only)
+    pub fn read_plan(
+        &self,
+        device: &mut Device<crate::device::Initialized>,
+        plan: &operations::ReadPlan,
+    ) -> Result<operations::PlanResult> {
+        if !matches!(self, Card::TypeF { .. }) {
+            return Err(crate::Error::UnsupportedOperation(
+                "read_plan is only supported for FeliCa (Type F) cards".into(),
+            ));
+        }
+        plan.read(self, device)
+    }
 }
 
 #[cfg(test)]
@@ -223,6 +423,19 @@ mod tests {
     use crate::transport::mock::MockTransport;
     use crate::types::DeviceType;
 
+    #[test]
+    fn card_chip_kind_from_pmm() {
+        let card = Card::new(
+            Idm::from_bytes([1; 8]),
+            Pmm::from_bytes([0x10, 0xf1, 0, 0, 0, 0, 0, 0]),
+            SystemCode::new(0x88b4),
+        );
+        assert_eq!(card.chip_kind(), Some(ChipKind::LiteS));
+
+        let card = Card::new_type_a(Uid::from_bytes(vec![1, 2, 3, 4]));
+        assert_eq!(card.chip_kind(), None);
+    }
+
     #[test]
     fn card_read_single_via_device() {
         let mut mock = MockTransport::new(DeviceType::S320);
@@ -256,19 +469,41 @@ mod tests {
         assert_eq!(block.as_bytes(), &[0x99; 16]);
     }
 
+    #[test]
+    fn card_read_blocks_reports_failing_element() {
+        use crate::test_support::{TEST_IDM, mock_device, status_frame};
+
+        // Error response: status flags only, second element rejected
+        let mut dev = mock_device(vec![status_frame(0x07, TEST_IDM, 0x02, 0xA8)]);
+        let card = crate::test_support::test_card();
+
+        let blocks = [
+            BlockElement::new(0, crate::types::AccessMode::Normal, 0),
+            BlockElement::new(0, crate::types::AccessMode::Normal, 0x40),
+        ];
+        match card.read_blocks(&mut dev, &[ServiceCode::new(0x090f)], &blocks) {
+            Err(err @ crate::Error::FelicaBlockStatus { index: 1, .. }) => {
+                assert_eq!(
+                    err.status_flag(),
+                    Some(crate::protocol::StatusFlag::IllegalBlockNumber)
+                );
+            }
+            other => panic!("expected FelicaBlockStatus, got {:?}", other),
+        }
+    }
+
     #[test]
     fn services_iterator_collects_service_codes() {
         let mut mock = MockTransport::new(DeviceType::S320);
 
-        // First response: found service (present=1) with code 0x1111
+        // First response: service code 0x1111
         let mut p1 = vec![0x0B];
         p1.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]); // idm
-        p1.push(1); // present
         p1.extend_from_slice(&0x1111u16.to_le_bytes());
-        // Second response: not found (present=0) -> termination
+        // Second response: 0xFFFF -> termination
         let mut p2 = vec![0x0B];
         p2.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
-        p2.push(0);
+        p2.extend_from_slice(&0xFFFFu16.to_le_bytes());
 
         // Reserve a handshake ack for model init, then the found/term frames.
         common::seed_init_and_frames(
@@ -411,4 +646,122 @@ mod tests {
             vec![SystemCode::SUICA.as_u16(), SystemCode::COMMON.as_u16()]
         );
     }
+
+    #[test]
+    fn card_read_service_all_via_device() {
+        use crate::test_support::{TEST_IDM, mock_device, read_fill_frame, response_frame};
+
+        // RequestBlockInformationEx: 6 assigned blocks, 0 free; FeliCa Lite
+        // reads at most 4 blocks per command: 4 + 2
+        let mut dev = mock_device(vec![
+            response_frame(0x1F, TEST_IDM, &[0, 0, 1, 6, 0, 0, 0]),
+            read_fill_frame(TEST_IDM, &[0, 1, 2, 3]),
+            read_fill_frame(TEST_IDM, &[4, 5]),
+        ]);
+        let card = crate::test_support::test_card_with_ic(0xf0);
+
+        let blocks = card
+            .read_service_all(&mut dev, ServiceCode::new(0x090f))
+            .unwrap();
+        let fills: Vec<u8> = blocks.iter().map(|b| b.as_bytes()[0]).collect();
+        assert_eq!(fills, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn card_read_service_all_falls_back_to_request_block_information() {
+        use crate::test_support::{TEST_IDM, mock_device, read_fill_frame, response_frame};
+
+        let mut dev = mock_device(vec![
+            // The card rejects RequestBlockInformationEx with an error status ...
+            response_frame(0x1F, TEST_IDM, &[0xFF, 0xA1]),
+            // ... and answers the plain RequestBlockInformation with 2 blocks.
+            response_frame(0x0F, TEST_IDM, &[1, 2, 0]),
+            read_fill_frame(TEST_IDM, &[0xA0, 0xA1]),
+        ]);
+        let card = crate::test_support::test_card();
+
+        let blocks = card
+            .read_service_all(&mut dev, ServiceCode::new(0x090f))
+            .unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].as_bytes(), &[0xA1; 16]);
+    }
+
+    #[test]
+    fn card_read_service_all_rejects_absent_service() {
+        // The card reports 0xFFFF assigned blocks for a node it lacks
+        let (mut dev, mock) =
+            crate::test_support::shared_mock_device(vec![crate::test_support::response_frame(
+                0x1F,
+                crate::test_support::TEST_IDM,
+                &[0, 0, 1, 0xff, 0xff, 0xff, 0xff],
+            )]);
+        let card = crate::test_support::test_card();
+
+        let r = card.read_service_all(&mut dev, ServiceCode::new(0x090f));
+        assert!(matches!(r, Err(crate::Error::UnsupportedOperation(_))));
+        // No read follows the block count query
+        assert_eq!(mock.borrow().sent.len(), 1);
+    }
+
+    #[test]
+    fn card_read_service_all_does_not_fall_back_for_another_card() {
+        // A different card answers RequestBlockInformationEx
+        let (mut dev, mock) =
+            crate::test_support::shared_mock_device(vec![crate::test_support::response_frame(
+                0x1F,
+                [9; 8],
+                &[0, 0, 1, 2, 0, 0, 0],
+            )]);
+        let card = crate::test_support::test_card();
+
+        let r = card.read_service_all(&mut dev, ServiceCode::new(0x090f));
+        assert!(matches!(r, Err(crate::Error::IdmMismatch { .. })));
+        assert_eq!(mock.borrow().sent.len(), 1);
+    }
+
+    #[test]
+    fn card_discover_tree_via_device() {
+        use crate::test_support::{TEST_IDM, mock_device, response_frame};
+
+        let search = |codes: &[u16]| {
+            let tail: Vec<u8> = codes.iter().flat_map(|c| c.to_le_bytes()).collect();
+            response_frame(0x0B, TEST_IDM, &tail)
+        };
+        let mut dev = mock_device(vec![
+            search(&[0x0000, 0xfffe]), // root area
+            search(&[0x1000, 0x13ff]), // sub-area
+            search(&[0x100b]),         // service in sub-area
+            search(&[0x4009]),         // service in root area
+            search(&[0xffff]),         // end
+        ]);
+        let card = crate::test_support::test_card();
+
+        let root = card.discover_tree(&mut dev).unwrap();
+        assert_eq!(root.end_code, 0xfffe);
+        assert_eq!(
+            root.services,
+            vec![ServiceNode {
+                code: ServiceCode::new(0x4009)
+            }]
+        );
+        assert_eq!(root.areas.len(), 1);
+        assert_eq!(root.areas[0].code, 0x1000);
+        assert_eq!(root.areas[0].services[0].code, ServiceCode::new(0x100b));
+    }
+
+    #[test]
+    fn card_read_blocks_rejects_service_requiring_authentication() {
+        // No read response is queued: the command must never be sent.
+        let (mut dev, mock) = crate::test_support::shared_mock_device(vec![]);
+        let card = crate::test_support::test_card();
+
+        // Cyclic read-only service that requires authentication
+        let svc = ServiceCode::new(0x090e);
+        match card.read_single(&mut dev, svc, 0) {
+            Err(crate::Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+        assert!(mock.borrow().sent.is_empty());
+    }
 }
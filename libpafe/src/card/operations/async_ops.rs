@@ -0,0 +1,261 @@
+//! Async versions of the card operations, for use with [`AsyncDevice`].
+//!
+//! Each function sends the same commands as its blocking namesake in
+//! `crate::card::operations` and interprets the responses the same way.
+//!
+//! [`AsyncDevice`]: crate::device::AsyncDevice
+
+use crate::card::Card;
+use crate::device::{AsyncDevice, Initialized};
+use crate::protocol::BlockInformation;
+use crate::types::{BlockData, BlockElement, CommandClass, ServiceCode, SystemCode};
+use crate::{Error, Result};
+
+use super::Exchange;
+use super::chunk::{Chunker, limit_for};
+
+/// Read multiple blocks with ReadWithoutEncryption, split into as many
+/// commands as the card accepts (see [`read_blocks`]).
+///
+/// [`read_blocks`]: crate::card::operations::read_blocks
+pub async fn read_blocks(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    services: &[ServiceCode],
+    blocks: &[BlockElement],
+) -> Result<Vec<BlockData>> {
+    let idm = *card.idm().ok_or_else(|| {
+        Error::UnsupportedOperation("Card does not have IDm (not a FeliCa card)".into())
+    })?;
+    super::ensure_unauthenticated_access(services)?;
+
+    let chunks = run_chunked(card, device, CommandClass::Read, blocks, |chunk| {
+        super::read::read_exchange(card, idm, services, chunk)
+    })
+    .await?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+/// Read a single block.
+pub async fn read_single(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    service: ServiceCode,
+    block: u16,
+) -> Result<BlockData> {
+    let element = BlockElement::new(0, crate::types::AccessMode::Normal, block);
+    let blocks = read_blocks(card, device, &[service], &[element]).await?;
+    blocks.into_iter().next().ok_or(Error::PollingFailed)
+}
+
+/// Read every block of a service, sizing the read with
+/// RequestBlockInformationEx (or RequestBlockInformation as fallback).
+pub async fn read_service_all(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    service: ServiceCode,
+) -> Result<Vec<BlockData>> {
+    super::ensure_unauthenticated_access(&[service])?;
+    let count = super::read::BlockCountQuery::new(card, service)?
+        .run_async(device)
+        .await?;
+    let elements = super::read::block_range(count as usize);
+    read_blocks(card, device, &[service], &elements).await
+}
+
+/// Write a single block with WriteWithoutEncryption.
+pub async fn write_single(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    service: ServiceCode,
+    block: BlockElement,
+    data: BlockData,
+) -> Result<()> {
+    super::write::write_single_exchange(card, service, block, data)?
+        .run_async(device)
+        .await
+}
+
+/// Write multiple blocks, split into as many commands as the card needs.
+/// A failure after the first command is reported as `Error::ChunkFailed`.
+pub async fn write_blocks(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    service: ServiceCode,
+    blocks: &[(BlockElement, BlockData)],
+) -> Result<()> {
+    if blocks.is_empty() {
+        return Ok(());
+    }
+    super::ensure_unauthenticated_access(&[service])?;
+    let idm = *card
+        .idm()
+        .ok_or_else(|| Error::UnsupportedOperation("Card does not have IDm".into()))?;
+
+    run_chunked(card, device, CommandClass::Write, blocks, |chunk| {
+        super::write::write_multi_exchange(card, idm, service, chunk)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Request the key versions for the provided service/node codes.
+pub async fn request_service_versions(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    node_codes: &[u16],
+) -> Result<Vec<u16>> {
+    super::service::service_versions_exchange(card, node_codes)?
+        .run_async(device)
+        .await
+}
+
+/// Query the current operating mode via RequestResponse.
+pub async fn request_response_mode(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+) -> Result<u8> {
+    super::service::response_mode_exchange(card)?
+        .run_async(device)
+        .await
+}
+
+/// Retrieve the list of published system codes for the card.
+pub async fn request_system_codes(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+) -> Result<Vec<SystemCode>> {
+    super::service::system_codes_exchange(card)?
+        .run_async(device)
+        .await
+}
+
+/// Request the block counts for the provided nodes via
+/// RequestBlockInformation.
+pub async fn request_block_information(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    node_codes: &[u16],
+) -> Result<Vec<u16>> {
+    super::service::block_information_exchange(card, node_codes)?
+        .run_async(device)
+        .await
+}
+
+/// Request assigned and free block counts for the provided nodes via
+/// RequestBlockInformationEx.
+pub async fn request_block_information_ex(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    node_codes: &[u16],
+) -> Result<Vec<BlockInformation>> {
+    super::service::block_information_ex_exchange(card, node_codes)?
+        .run_async(device)
+        .await
+}
+
+/// Async variant of `chunk::run_chunked`: one exchange per chunk, results
+/// in request order.
+async fn run_chunked<E, R>(
+    card: &Card,
+    device: &mut AsyncDevice<Initialized>,
+    class: CommandClass,
+    items: &[E],
+    make: impl Fn(&[E]) -> Exchange<R>,
+) -> Result<Vec<R>> {
+    let learned = card.idm().and_then(|idm| device.block_limit(idm, class));
+    let mut chunker = Chunker::new(items.len(), limit_for(card, learned, class));
+    let mut out = Vec::new();
+
+    while let Some(range) = chunker.next_range() {
+        match make(&items[range]).run_async(device).await {
+            Ok(result) => {
+                out.push(result);
+                chunker.advance();
+            }
+            Err(e) => {
+                let limit = chunker.shrink_or_fail(e)?;
+                if let Some(idm) = card.idm() {
+                    device.set_block_limit(*idm, class, limit);
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        TEST_IDM, async_mock_device, block_elements as elements, read_fill_frame, response_frame,
+        status_frame, test_card_with_ic as card,
+    };
+    use crate::types::Idm;
+
+    #[tokio::test]
+    async fn read_blocks_halves_rejected_chunk() {
+        let mut dev = async_mock_device(vec![
+            status_frame(0x07, TEST_IDM, 0xFF, 0xA2),
+            read_fill_frame(TEST_IDM, &[0, 1]),
+            read_fill_frame(TEST_IDM, &[2, 3]),
+        ])
+        .await;
+        let blocks = read_blocks(
+            &card(0x01),
+            &mut dev,
+            &[ServiceCode::new(0x090f)],
+            &elements(4),
+        )
+        .await
+        .unwrap();
+        let fills: Vec<u8> = blocks.iter().map(|b| b.as_bytes()[0]).collect();
+        assert_eq!(fills, vec![0, 1, 2, 3]);
+        assert_eq!(
+            dev.block_limit(&Idm::from_bytes(TEST_IDM), CommandClass::Read),
+            Some(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn write_blocks_reports_failed_chunk() {
+        // FeliCa Lite writes one block per command; the second one fails
+        let mut dev = async_mock_device(vec![
+            status_frame(0x09, TEST_IDM, 0, 0),
+            status_frame(0x09, TEST_IDM, 0x01, 0xA5),
+        ])
+        .await;
+        let blocks: Vec<_> = elements(2)
+            .into_iter()
+            .map(|e| (e, BlockData::from_bytes([0x11; 16])))
+            .collect();
+        match write_blocks(&card(0xf0), &mut dev, ServiceCode::new(0x0009), &blocks).await {
+            Err(Error::ChunkFailed { chunk: 1, .. }) => {}
+            other => panic!("expected ChunkFailed, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn request_response_mode_returns_mode() {
+        let mut dev = async_mock_device(vec![response_frame(0x05, TEST_IDM, &[0x02])]).await;
+        assert_eq!(
+            request_response_mode(&card(0x01), &mut dev).await.unwrap(),
+            0x02
+        );
+    }
+
+    #[tokio::test]
+    async fn read_service_all_falls_back_and_rejects_absent_service() {
+        // RequestBlockInformationEx fails; the plain command reports 0xFFFF
+        let mut dev = async_mock_device(vec![
+            status_frame(0x1F, TEST_IDM, 0xFF, 0xA1),
+            response_frame(0x0F, TEST_IDM, &[1, 0xff, 0xff]),
+        ])
+        .await;
+        match read_service_all(&card(0x01), &mut dev, ServiceCode::new(0x090f)).await {
+            Err(Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+    }
+}
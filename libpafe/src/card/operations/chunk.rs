@@ -0,0 +1,218 @@
+use crate::device::Device;
+use crate::protocol::StatusFlag;
+use crate::types::CommandClass;
+use crate::{Error, Result};
+
+/// Blocks-per-command limit for `card`: the limit learned earlier on this
+/// device for the card's IDm, else the limit of the chip family its PMm
+/// identifies.
+pub(crate) fn block_limit(
+    card: &crate::card::Card,
+    device: &Device<crate::device::Initialized>,
+    class: CommandClass,
+) -> usize {
+    let learned = card.idm().and_then(|idm| device.block_limit(idm, class));
+    limit_for(card, learned, class)
+}
+
+/// `learned`, else the blocks-per-command limit of the card's chip family.
+pub(crate) fn limit_for(
+    card: &crate::card::Card,
+    learned: Option<usize>,
+    class: CommandClass,
+) -> usize {
+    let chip = card.chip_kind().map(|kind| match class {
+        CommandClass::Write => kind.max_write_blocks(),
+        _ => kind.max_read_blocks(),
+    });
+    learned.or(chip).unwrap_or(1)
+}
+
+/// Run `op` over `items` in chunks the card accepts, concatenating the
+/// results in the original order.
+///
+/// A chunk the card rejects with "illegal number of blocks" (0xA2) is
+/// retried at half the size and the smaller limit is cached on the device
+/// for the card's IDm. Any other failure of a request that needed more
+/// than one command is reported as `Error::ChunkFailed`; a request that
+/// fits in one command returns the command's error unchanged.
+pub(crate) fn run_chunked<E, T>(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    class: CommandClass,
+    items: &[E],
+    mut op: impl FnMut(&mut Device<crate::device::Initialized>, &[E]) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    let mut chunker = Chunker::new(items.len(), block_limit(card, device, class));
+    let mut out = Vec::with_capacity(items.len());
+
+    while let Some(range) = chunker.next_range() {
+        match op(device, &items[range]) {
+            Ok(results) => {
+                out.extend(results);
+                chunker.advance();
+            }
+            Err(e) => {
+                let limit = chunker.shrink_or_fail(e)?;
+                if let Some(idm) = card.idm() {
+                    device.set_block_limit(*idm, class, limit);
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Progress through a chunked request, shared by the blocking and async
+/// chunk loops.
+pub(crate) struct Chunker {
+    len: usize,
+    limit: usize,
+    chunked: bool,
+    start: usize,
+    chunk: usize,
+}
+
+impl Chunker {
+    pub(crate) fn new(len: usize, limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            len,
+            limit,
+            chunked: len > limit,
+            start: 0,
+            chunk: 0,
+        }
+    }
+
+    /// Items of the next command, `None` once every item was handled.
+    pub(crate) fn next_range(&self) -> Option<std::ops::Range<usize>> {
+        (self.start < self.len).then(|| self.start..self.end())
+    }
+
+    /// Mark the current chunk as done.
+    pub(crate) fn advance(&mut self) {
+        self.start = self.end();
+        self.chunk += 1;
+    }
+
+    /// Handle the failure of the current chunk: returns the halved limit
+    /// to retry with, or the error that ends the request.
+    pub(crate) fn shrink_or_fail(&mut self, e: Error) -> Result<usize> {
+        let (start, end) = (self.start, self.end());
+        if e.status_flag() == Some(StatusFlag::IllegalNumberOfBlock) && end - start > 1 {
+            self.limit = (end - start) / 2;
+            self.chunked = true;
+            return Ok(self.limit);
+        }
+        if self.chunked || self.chunk > 0 {
+            return Err(Error::ChunkFailed {
+                chunk: self.chunk,
+                start,
+                end,
+                source: Box::new(e),
+            });
+        }
+        Err(e)
+    }
+
+    fn end(&self) -> usize {
+        self.len.min(self.start + self.limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        TEST_IDM, block_elements as elements, mock_device, read_fill_frame, status_frame,
+        test_card_with_ic as card,
+    };
+    use crate::types::{BlockData, Idm, ServiceCode};
+
+    #[test]
+    fn rejected_chunk_is_halved_and_limit_cached() {
+        // Standard chip: 5 blocks fit one command, but the card rejects it
+        let mut dev = mock_device(vec![
+            status_frame(0x07, TEST_IDM, 0xFF, 0xA2),
+            read_fill_frame(TEST_IDM, &[0, 1]),
+            read_fill_frame(TEST_IDM, &[2, 3]),
+            read_fill_frame(TEST_IDM, &[4]),
+            read_fill_frame(TEST_IDM, &[0, 1]),
+            read_fill_frame(TEST_IDM, &[2]),
+        ]);
+        let card = card(0x01);
+        let service = [ServiceCode::new(0x090f)];
+
+        let blocks =
+            crate::card::operations::read_blocks(&card, &mut dev, &service, &elements(5)).unwrap();
+        let fills: Vec<u8> = blocks.iter().map(|b| b.as_bytes()[0]).collect();
+        assert_eq!(fills, vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            dev.block_limit(&Idm::from_bytes(TEST_IDM), CommandClass::Read),
+            Some(2)
+        );
+
+        // The learned limit is used straight away on the next request
+        let blocks =
+            crate::card::operations::read_blocks(&card, &mut dev, &service, &elements(3)).unwrap();
+        assert_eq!(blocks.len(), 3);
+    }
+
+    #[test]
+    fn failure_after_halving_is_reported_as_chunk() {
+        // The request fits one command until the card rejects it; the
+        // first halved chunk then fails outright
+        let mut dev = mock_device(vec![
+            status_frame(0x07, TEST_IDM, 0xFF, 0xA2),
+            status_frame(0x07, TEST_IDM, 0x01, 0xA5),
+        ]);
+        let service = [ServiceCode::new(0x090f)];
+
+        match crate::card::operations::read_blocks(&card(0x01), &mut dev, &service, &elements(5)) {
+            Err(Error::ChunkFailed {
+                chunk: 0,
+                start: 0,
+                end: 2,
+                ..
+            }) => {}
+            other => panic!("expected ChunkFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn failing_chunk_is_reported() {
+        // FeliCa Lite writes one block per command; the second one fails
+        let mut dev = mock_device(vec![
+            status_frame(0x09, TEST_IDM, 0, 0),
+            status_frame(0x09, TEST_IDM, 0x01, 0xA5),
+        ]);
+        let blocks: Vec<_> = elements(3)
+            .into_iter()
+            .map(|e| (e, BlockData::from_bytes([0x11; 16])))
+            .collect();
+
+        match crate::card::operations::write::write_blocks(
+            &card(0xf0),
+            &mut dev,
+            ServiceCode::new(0x0009),
+            &blocks,
+        ) {
+            Err(Error::ChunkFailed {
+                chunk: 1,
+                start: 1,
+                end: 2,
+                source,
+            }) => assert!(matches!(
+                *source,
+                Error::FelicaBlockStatus {
+                    index: 0,
+                    status2: 0xA5,
+                    ..
+                }
+            )),
+            other => panic!("expected ChunkFailed, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::Result;
+use crate::device::Device;
+use crate::types::{BlockData, ServiceCode, ServiceType};
+
+/// Read the latest `count` records of a cyclic service, oldest first.
+///
+/// A cyclic service keeps its newest record in block 0 and older records
+/// in increasing block numbers. The request is clamped to the number of
+/// blocks assigned to the service, and the records are returned in
+/// chronological order so the last element is the most recent one.
+pub fn read_cyclic_latest(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+    count: usize,
+) -> Result<Vec<BlockData>> {
+    super::ensure_service_type(service, ServiceType::Cyclic)?;
+    super::ensure_unauthenticated_access(&[service])?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let assigned = super::read::service_block_count(card, device, service)? as usize;
+    let mut records = super::read::read_block_range(card, device, service, count.min(assigned))?;
+    records.reverse();
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, mock_device, read_fill_frame, response_frame, test_card};
+
+    fn block_info_ex_frame(assigned: u16) -> Vec<u8> {
+        let mut tail = vec![0, 0, 1];
+        tail.extend_from_slice(&assigned.to_le_bytes());
+        tail.extend_from_slice(&0u16.to_le_bytes());
+        response_frame(0x1F, TEST_IDM, &tail)
+    }
+
+    #[test]
+    fn read_cyclic_latest_returns_oldest_first() {
+        // Block 0 (fill 0x30) is the newest record
+        let mut dev = mock_device(vec![
+            block_info_ex_frame(10),
+            read_fill_frame(TEST_IDM, &[0x30, 0x20, 0x10]),
+        ]);
+        let records =
+            read_cyclic_latest(&test_card(), &mut dev, ServiceCode::new(0x090f), 3).unwrap();
+        let fills: Vec<u8> = records.iter().map(|b| b.as_bytes()[0]).collect();
+        assert_eq!(fills, vec![0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn read_cyclic_latest_clamps_to_assigned_blocks() {
+        let mut dev = mock_device(vec![
+            block_info_ex_frame(2),
+            read_fill_frame(TEST_IDM, &[0x02, 0x01]),
+        ]);
+        let records =
+            read_cyclic_latest(&test_card(), &mut dev, ServiceCode::new(0x090f), 20).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].as_bytes(), &[0x02; 16]);
+    }
+
+    #[test]
+    fn read_cyclic_latest_rejects_random_service() {
+        let mut dev = mock_device(vec![]);
+        match read_cyclic_latest(&test_card(), &mut dev, ServiceCode::new(0x000b), 1) {
+            Err(crate::Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+    }
+}
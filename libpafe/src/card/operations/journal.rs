@@ -0,0 +1,541 @@
+use crate::device::Device;
+use crate::types::{AccessMode, BlockData, BlockElement, ServiceCode, ServiceType};
+use crate::{Error, Result};
+
+/// Magic bytes at the start of a journal header block.
+const MAGIC: [u8; 2] = *b"LJ";
+
+/// Target block numbers stored per index block.
+const NUMBERS_PER_INDEX_BLOCK: usize = 8;
+
+const STATE_IDLE: u8 = 0;
+const STATE_STAGING: u8 = 1;
+const STATE_COMMITTED: u8 = 2;
+
+/// Reserved blocks of a writable random service used to make multi-block
+/// writes tear-safe.
+///
+/// Layout, starting at `first_block`:
+/// - one header block: magic `"LJ"`, state, block count and the target
+///   service code (little endian);
+/// - index blocks holding the target block numbers (u16 LE, 8 per block);
+/// - `capacity` data blocks holding the staged contents.
+///
+/// A transaction stages the new contents, then writes the header in the
+/// committed state, copies the data to the target service and finally
+/// marks the header idle. A single-block write is atomic on the card, so
+/// the header write is the commit point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Journal {
+    service: ServiceCode,
+    first_block: u16,
+    capacity: u8,
+}
+
+/// What [`Journal::recover`] found and did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryOutcome {
+    /// No transaction was in progress.
+    Clean,
+    /// A transaction was interrupted before its commit; the target blocks
+    /// were never touched and the staged data was discarded.
+    RolledBack,
+    /// A committed transaction was interrupted; its data was copied to the
+    /// target blocks.
+    Completed,
+}
+
+/// Decoded journal header block.
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    state: u8,
+    count: u8,
+    target: ServiceCode,
+}
+
+impl Header {
+    fn encode(&self) -> BlockData {
+        let mut b = [0u8; 16];
+        b[..2].copy_from_slice(&MAGIC);
+        b[2] = self.state;
+        b[3] = self.count;
+        b[4..6].copy_from_slice(&self.target.as_u16().to_le_bytes());
+        BlockData::from_bytes(b)
+    }
+
+    /// `None` when the block does not hold a journal header (e.g. a fresh
+    /// card).
+    fn decode(block: &BlockData) -> Option<Self> {
+        let b = block.as_bytes();
+        if b[..2] != MAGIC {
+            return None;
+        }
+        Some(Self {
+            state: b[2],
+            count: b[3],
+            target: ServiceCode::new(u16::from_le_bytes([b[4], b[5]])),
+        })
+    }
+}
+
+impl Journal {
+    /// Journal occupying blocks of `service` from `first_block`, able to
+    /// stage up to `capacity` blocks per transaction.
+    ///
+    /// Fails with [`Error::Journal`] when `capacity` is zero or the
+    /// reserved blocks would run past block number 0xffff.
+    pub fn new(service: ServiceCode, first_block: u16, capacity: u8) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::Journal("journal capacity must be at least 1".into()));
+        }
+        let journal = Self {
+            service,
+            first_block,
+            capacity,
+        };
+        if first_block
+            .checked_add(journal.blocks_needed() - 1)
+            .is_none()
+        {
+            return Err(Error::Journal(format!(
+                "{} journal blocks from block {:#06x} exceed the block range",
+                journal.blocks_needed(),
+                first_block
+            )));
+        }
+        Ok(journal)
+    }
+
+    /// Service holding the journal.
+    pub fn service(&self) -> ServiceCode {
+        self.service
+    }
+
+    /// Number of reserved blocks the journal occupies.
+    pub fn blocks_needed(&self) -> u16 {
+        1 + self.index_blocks() + self.capacity as u16
+    }
+
+    fn index_blocks(&self) -> u16 {
+        (self.capacity as usize).div_ceil(NUMBERS_PER_INDEX_BLOCK) as u16
+    }
+
+    fn element(block: u16) -> BlockElement {
+        BlockElement::new(0, AccessMode::Normal, block)
+    }
+
+    fn check_service(&self) -> Result<()> {
+        check_writable(self.service, "journal")
+    }
+
+    /// Reject targets `apply` must not write: the journal's own service
+    /// and anything [`check_writable`] refuses.
+    fn check_target(&self, target: ServiceCode) -> Result<()> {
+        if target == self.service {
+            return Err(Error::Journal(
+                "the target service must differ from the journal service".into(),
+            ));
+        }
+        check_writable(target, "target")
+    }
+
+    fn write_header(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+        header: Header,
+    ) -> Result<()> {
+        super::write::write_single(
+            card,
+            device,
+            self.service,
+            Self::element(self.first_block),
+            header.encode(),
+        )
+    }
+
+    /// Write `blocks` (block number, contents) of `target` as one
+    /// transaction. An interrupted transaction left on the card is
+    /// recovered first.
+    pub fn write(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+        target: ServiceCode,
+        blocks: &[(u16, BlockData)],
+    ) -> Result<()> {
+        if blocks.is_empty() {
+            return Ok(());
+        }
+        if blocks.len() > self.capacity as usize {
+            return Err(Error::Journal(format!(
+                "{} blocks exceed the journal capacity of {}",
+                blocks.len(),
+                self.capacity
+            )));
+        }
+        self.check_service()?;
+        // A target `apply` cannot write would leave a committed
+        // transaction that every later `recover` fails to finish
+        self.check_target(target)?;
+
+        self.recover(card, device)?;
+
+        let count = blocks.len() as u8;
+        self.write_header(
+            card,
+            device,
+            Header {
+                state: STATE_STAGING,
+                count,
+                target,
+            },
+        )?;
+
+        let mut staged = Vec::new();
+        for (i, chunk) in blocks.chunks(NUMBERS_PER_INDEX_BLOCK).enumerate() {
+            let mut b = [0u8; 16];
+            for (slot, (number, _)) in b.chunks_exact_mut(2).zip(chunk) {
+                slot.copy_from_slice(&number.to_le_bytes());
+            }
+            let block = self.first_block + 1 + i as u16;
+            staged.push((Self::element(block), BlockData::from_bytes(b)));
+        }
+        let data_start = self.first_block + 1 + self.index_blocks();
+        for (i, (_, data)) in blocks.iter().enumerate() {
+            staged.push((Self::element(data_start + i as u16), *data));
+        }
+        super::write::write_blocks(card, device, self.service, &staged)?;
+
+        self.write_header(
+            card,
+            device,
+            Header {
+                state: STATE_COMMITTED,
+                count,
+                target,
+            },
+        )?;
+        self.apply(card, device, target, blocks)?;
+        self.write_header(
+            card,
+            device,
+            Header {
+                state: STATE_IDLE,
+                count: 0,
+                target,
+            },
+        )
+    }
+
+    /// Finish or roll back a transaction interrupted by the card leaving
+    /// the field. Call this the next time the card is seen.
+    pub fn recover(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+    ) -> Result<RecoveryOutcome> {
+        self.check_service()?;
+        let raw = super::read_single(card, device, self.service, self.first_block)?;
+        let Some(header) = Header::decode(&raw) else {
+            return Ok(RecoveryOutcome::Clean);
+        };
+        let idle = Header {
+            state: STATE_IDLE,
+            count: 0,
+            target: header.target,
+        };
+
+        match header.state {
+            STATE_IDLE => Ok(RecoveryOutcome::Clean),
+            STATE_STAGING => {
+                self.write_header(card, device, idle)?;
+                Ok(RecoveryOutcome::RolledBack)
+            }
+            STATE_COMMITTED => {
+                // The target comes from the card: a corrupt header or one
+                // written under another layout must not redirect the copy
+                self.check_target(header.target).map_err(|e| match e {
+                    Error::Journal(_) => e,
+                    other => Error::Journal(format!("committed header: {other}")),
+                })?;
+                let blocks = self.read_staged(card, device, header.count)?;
+                self.apply(card, device, header.target, &blocks)?;
+                self.write_header(card, device, idle)?;
+                Ok(RecoveryOutcome::Completed)
+            }
+            other => Err(Error::Journal(format!(
+                "unknown journal state {:#04x}",
+                other
+            ))),
+        }
+    }
+
+    /// Read back the block numbers and contents of a committed transaction.
+    fn read_staged(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+        count: u8,
+    ) -> Result<Vec<(u16, BlockData)>> {
+        if count == 0 || count > self.capacity {
+            return Err(Error::Journal(format!(
+                "committed transaction holds {} blocks, capacity is {}",
+                count, self.capacity
+            )));
+        }
+        let count = count as usize;
+        let index_count = count.div_ceil(NUMBERS_PER_INDEX_BLOCK) as u16;
+        let data_start = self.first_block + 1 + self.index_blocks();
+
+        let elements: Vec<BlockElement> = (0..index_count)
+            .map(|i| Self::element(self.first_block + 1 + i))
+            .chain((0..count as u16).map(|i| Self::element(data_start + i)))
+            .collect();
+        let read = super::read_blocks(card, device, &[self.service], &elements)?;
+        let (index, data) = read.split_at(index_count as usize);
+
+        let numbers = index
+            .iter()
+            .flat_map(|b| b.as_bytes().chunks_exact(2))
+            .map(|n| u16::from_le_bytes([n[0], n[1]]));
+        Ok(numbers.zip(data.iter().copied()).collect())
+    }
+
+    fn apply(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+        target: ServiceCode,
+        blocks: &[(u16, BlockData)],
+    ) -> Result<()> {
+        let writes: Vec<(BlockElement, BlockData)> = blocks
+            .iter()
+            .map(|(n, d)| (Self::element(*n), *d))
+            .collect();
+        super::write::write_blocks(card, device, target, &writes)
+    }
+}
+
+/// Reject services the journal cannot write block by block: anything but
+/// a writable random service without authentication.
+fn check_writable(service: ServiceCode, role: &str) -> Result<()> {
+    super::ensure_service_type(service, ServiceType::Random)?;
+    super::ensure_unauthenticated_access(&[service])?;
+    if service.is_read_only() {
+        return Err(Error::Journal(format!(
+            "{role} service {:#06x} is read-only",
+            service.as_u16()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Frame;
+    use crate::test_support::{
+        TEST_IDM, mock_device, read_frame, shared_mock_device, status_frame, test_card,
+    };
+
+    fn write_ok() -> Vec<u8> {
+        status_frame(0x09, TEST_IDM, 0, 0)
+    }
+
+    fn header(state: u8, count: u8, target: u16) -> [u8; 16] {
+        *Header {
+            state,
+            count,
+            target: ServiceCode::new(target),
+        }
+        .encode()
+        .as_bytes()
+    }
+
+    fn journal() -> Journal {
+        Journal::new(ServiceCode::new(0x1009), 0, 4).unwrap()
+    }
+
+    #[test]
+    fn blocks_needed_covers_header_index_and_data() {
+        assert_eq!(journal().blocks_needed(), 6);
+        assert_eq!(
+            Journal::new(ServiceCode::new(0x1009), 0, 9)
+                .unwrap()
+                .blocks_needed(),
+            12
+        );
+    }
+
+    #[test]
+    fn new_rejects_empty_or_out_of_range_journal() {
+        let service = ServiceCode::new(0x1009);
+        assert!(matches!(
+            Journal::new(service, 0, 0),
+            Err(Error::Journal(_))
+        ));
+        // Capacity 4 needs 6 blocks: 0xfffa..=0xffff still fits
+        assert!(Journal::new(service, 0xfffa, 4).is_ok());
+        assert!(matches!(
+            Journal::new(service, 0xfffb, 4),
+            Err(Error::Journal(_))
+        ));
+        assert!(matches!(
+            Journal::new(service, u16::MAX, 1),
+            Err(Error::Journal(_))
+        ));
+    }
+
+    /// A WriteWithoutEncryption the device sent: service and
+    /// (block number, contents) pairs.
+    type SentWrite = (u16, Vec<(u16, [u8; 16])>);
+
+    /// Decode the WriteWithoutEncryption commands among the sent frames,
+    /// in order.
+    fn sent_writes(sent: &[Vec<u8>]) -> Vec<SentWrite> {
+        sent.iter()
+            .filter_map(|frame| Frame::decode(frame).ok())
+            .filter(|p| p[0] == 0x08)
+            .map(|p| {
+                let service = u16::from_le_bytes([p[10], p[11]]);
+                let count = p[12] as usize;
+                let mut pos = 13;
+                let mut numbers = Vec::new();
+                for _ in 0..count {
+                    let (element, used) = BlockElement::decode(&p[pos..]).unwrap();
+                    numbers.push(element.block_number);
+                    pos += used;
+                }
+                let blocks = numbers
+                    .into_iter()
+                    .zip(p[pos..].chunks_exact(16))
+                    .map(|(n, d)| (n, d.try_into().unwrap()))
+                    .collect();
+                (service, blocks)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn write_stages_commits_applies_and_clears() {
+        let (mut dev, mock) = shared_mock_device(vec![
+            read_frame(TEST_IDM, &[[0; 16]]), // recover: fresh card
+            write_ok(),                       // staging header
+            write_ok(),                       // index + data
+            write_ok(),                       // committed header
+            write_ok(),                       // apply to target
+            write_ok(),                       // idle header
+        ]);
+        let blocks = [(3, BlockData::from_bytes([0x33; 16]))];
+        journal()
+            .write(&test_card(), &mut dev, ServiceCode::new(0x2009), &blocks)
+            .unwrap();
+
+        let mut index = [0u8; 16];
+        index[..2].copy_from_slice(&3u16.to_le_bytes());
+        assert_eq!(
+            sent_writes(&mock.borrow().sent),
+            vec![
+                (0x1009, vec![(0, header(1, 1, 0x2009))]),
+                // Index block, then the data block after all 1 index blocks
+                (0x1009, vec![(1, index), (2, [0x33; 16])]),
+                // Commit point: only now is the target touched
+                (0x1009, vec![(0, header(2, 1, 0x2009))]),
+                (0x2009, vec![(3, [0x33; 16])]),
+                (0x1009, vec![(0, header(0, 0, 0x2009))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn recover_rolls_back_uncommitted_transaction() {
+        let (mut dev, mock) = shared_mock_device(vec![
+            read_frame(TEST_IDM, &[header(1, 2, 0x2009)]),
+            write_ok(),
+        ]);
+        assert_eq!(
+            journal().recover(&test_card(), &mut dev).unwrap(),
+            RecoveryOutcome::RolledBack
+        );
+        // Only the header goes back to idle; the target is never written
+        assert_eq!(
+            sent_writes(&mock.borrow().sent),
+            vec![(0x1009, vec![(0, header(0, 0, 0x2009))])]
+        );
+    }
+
+    #[test]
+    fn recover_completes_committed_transaction() {
+        let mut index = [0u8; 16];
+        index[..2].copy_from_slice(&7u16.to_le_bytes());
+        index[2..4].copy_from_slice(&9u16.to_le_bytes());
+        let (mut dev, mock) = shared_mock_device(vec![
+            read_frame(TEST_IDM, &[header(2, 2, 0x2009)]),
+            read_frame(TEST_IDM, &[index, [0x77; 16], [0x99; 16]]),
+            write_ok(), // apply
+            write_ok(), // idle header
+        ]);
+        assert_eq!(
+            journal().recover(&test_card(), &mut dev).unwrap(),
+            RecoveryOutcome::Completed
+        );
+        assert_eq!(
+            sent_writes(&mock.borrow().sent),
+            vec![
+                (0x2009, vec![(7, [0x77; 16]), (9, [0x99; 16])]),
+                (0x1009, vec![(0, header(0, 0, 0x2009))]),
+            ]
+        );
+    }
+
+    #[test]
+    fn recover_rejects_committed_header_naming_the_journal() {
+        let (mut dev, mock) =
+            shared_mock_device(vec![read_frame(TEST_IDM, &[header(2, 1, 0x1009)])]);
+        match journal().recover(&test_card(), &mut dev) {
+            Err(Error::Journal(msg)) => assert!(msg.contains("journal service")),
+            other => panic!("expected Journal error, got {:?}", other),
+        }
+        // Only the header was read; nothing was written
+        assert_eq!(mock.borrow().sent.len(), 1);
+        assert!(sent_writes(&mock.borrow().sent).is_empty());
+    }
+
+    #[test]
+    fn recover_rejects_committed_header_naming_a_read_only_target() {
+        let (mut dev, mock) =
+            shared_mock_device(vec![read_frame(TEST_IDM, &[header(2, 1, 0x200B)])]);
+        assert!(matches!(
+            journal().recover(&test_card(), &mut dev),
+            Err(Error::Journal(_))
+        ));
+        assert_eq!(mock.borrow().sent.len(), 1);
+    }
+
+    #[test]
+    fn write_rejects_targets_it_cannot_apply() {
+        // Read-only random, cyclic and purse services
+        for target in [0x200B, 0x200D, 0x2011] {
+            let (mut dev, mock) = shared_mock_device(vec![]);
+            let blocks = [(0, BlockData::from_bytes([0; 16]))];
+            assert!(
+                journal()
+                    .write(&test_card(), &mut dev, ServiceCode::new(target), &blocks)
+                    .is_err()
+            );
+            // Rejected before anything reaches the card
+            assert!(mock.borrow().sent.is_empty());
+        }
+    }
+
+    #[test]
+    fn write_rejects_more_blocks_than_capacity() {
+        let mut dev = mock_device(vec![]);
+        let blocks = vec![(0, BlockData::from_bytes([0; 16])); 5];
+        match journal().write(&test_card(), &mut dev, ServiceCode::new(0x2009), &blocks) {
+            Err(Error::Journal(msg)) => assert!(msg.contains("capacity")),
+            other => panic!("expected Journal error, got {:?}", other),
+        }
+    }
+}
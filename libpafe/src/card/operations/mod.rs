@@ -1,3 +1,10 @@
+#[cfg(feature = "async")]
+pub mod async_ops;
+mod chunk;
+pub mod cyclic;
+pub mod journal;
+pub mod plan;
+pub mod purse;
 pub mod read;
 pub mod service;
 pub mod write;
@@ -5,7 +12,141 @@ pub mod write;
 // Re-export commonly used functions/types at the operations root so callers
 // can use `crate::card::operations::read_blocks(...)` and receive the
 // iterator type as `crate::card::operations::ServiceIterator`.
-pub use read::{read_blocks, read_single};
+pub use cyclic::read_cyclic_latest;
+pub use journal::{Journal, RecoveryOutcome};
+pub use plan::{PlanResult, ReadPlan};
+pub use purse::{PurseBlock, read_purse};
+pub use read::{read_blocks, read_service_all, read_single};
 pub use service::{
-    ServiceIterator, request_response_mode, request_service_versions, request_system_codes,
+    ServiceIterator, discover_tree, request_block_information, request_block_information_ex,
+    request_response_mode, request_service_versions, request_system_codes,
 };
+pub use write::{WriteOptions, WriteReport, write_blocks_with_options};
+
+use crate::protocol::{Command, Response};
+use crate::types::{CommandClass, Idm, ServiceCode, ServiceType};
+use crate::{Error, Result};
+
+/// A single card command together with its receive timeout and the
+/// interpretation of its response. The blocking operations and their
+/// async counterparts build the same `Exchange` and only differ in how
+/// they run it.
+pub(crate) struct Exchange<T> {
+    idm: Idm,
+    cmd: Command,
+    timeout_ms: u64,
+    finish: fn(Idm, Response) -> Result<T>,
+}
+
+impl<T> Exchange<T> {
+    /// Address `cmd` to the card with `idm`; the timeout is derived from
+    /// the card's PMm for `class` and `n` blocks or nodes.
+    pub(crate) fn new(
+        card: &crate::card::Card,
+        idm: Idm,
+        cmd: Command,
+        class: CommandClass,
+        n: usize,
+        finish: fn(Idm, Response) -> Result<T>,
+    ) -> Self {
+        Self {
+            idm,
+            cmd,
+            timeout_ms: command_timeout(card, class, n),
+            finish,
+        }
+    }
+
+    /// Run the exchange on a blocking device.
+    pub(crate) fn run(
+        self,
+        device: &mut crate::device::Device<crate::device::Initialized>,
+    ) -> Result<T> {
+        let resp = device.execute(self.cmd, self.timeout_ms)?;
+        (self.finish)(self.idm, resp)
+    }
+
+    /// Run the exchange on an async device.
+    #[cfg(feature = "async")]
+    pub(crate) async fn run_async(
+        self,
+        device: &mut crate::device::AsyncDevice<crate::device::Initialized>,
+    ) -> Result<T> {
+        let resp = device.execute(self.cmd, self.timeout_ms).await?;
+        (self.finish)(self.idm, resp)
+    }
+}
+
+/// Receive timeout for a command sent to `card`, derived from the maximum
+/// response time its PMm advertises for `class` and `n` blocks or nodes.
+fn command_timeout(card: &crate::card::Card, class: CommandClass, n: usize) -> u64 {
+    card.pmm()
+        .map_or(crate::utils::DEFAULT_READ_TIMEOUT_MS, |pmm| {
+            crate::utils::response_timeout_ms(pmm.max_response_time(class, n))
+        })
+}
+
+/// Reject services whose attributes require authentication before a
+/// "without encryption" command is sent to them.
+fn ensure_unauthenticated_access(services: &[ServiceCode]) -> Result<()> {
+    match services.iter().find(|s| s.requires_authentication()) {
+        Some(svc) => Err(Error::UnsupportedOperation(format!(
+            "service {:#06x} requires authentication; \
+             access without encryption is not possible",
+            svc.as_u16()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Whether a failed RequestBlockInformationEx suggests the card lacks the
+/// command, so RequestBlockInformation is worth trying: no answer or an
+/// error status. Any other error (a different card answered, the reader is
+/// gone) is returned instead of being followed by a second command.
+fn block_information_ex_unsupported(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Timeout | Error::FelicaStatus { .. } | Error::FelicaBlockStatus { .. }
+    )
+}
+
+/// Reject services whose type differs from the one an operation expects.
+fn ensure_service_type(service: ServiceCode, expected: ServiceType) -> Result<()> {
+    match service.service_type() {
+        Some(actual) if actual == expected => Ok(()),
+        actual => Err(Error::UnsupportedOperation(format!(
+            "service {:#06x} is not a {expected:?} service (found {actual:?})",
+            service.as_u16()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use crate::types::{Idm, Pmm, SystemCode, Uid};
+
+    #[test]
+    fn command_timeout_scales_with_block_count() {
+        // Read byte: E=1, B=3, A=0 -> T × (4n + 1) × 4
+        let card = Card::new(
+            Idm::from_bytes([0; 8]),
+            Pmm::from_bytes([0, 0, 0, 0, 0, 0x58, 0, 0]),
+            SystemCode::new(0x0003),
+        );
+        let one = command_timeout(&card, CommandClass::Read, 1);
+        let many = command_timeout(&card, CommandClass::Read, 15);
+        assert_eq!(one, 7 + crate::utils::RESPONSE_TIME_MARGIN_MS);
+        assert_eq!(many, 74 + crate::utils::RESPONSE_TIME_MARGIN_MS);
+    }
+
+    #[test]
+    fn command_timeout_without_pmm_uses_default() {
+        let card = Card::new_type_a(Uid::from_bytes(vec![1, 2, 3, 4]));
+        assert_eq!(
+            command_timeout(&card, CommandClass::Read, 4),
+            crate::utils::DEFAULT_READ_TIMEOUT_MS
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::device::Device;
+use crate::types::{AccessMode, BlockData, BlockElement, ServiceCode};
+use crate::{Error, Result};
+
+/// Blocks read by a `ReadPlan`, keyed by (service, block number).
+pub type PlanResult = HashMap<(ServiceCode, u16), BlockData>;
+
+/// One ReadWithoutEncryption command of a plan: its service list, block
+/// list (service indexes relative to that list) and the key of each block.
+#[derive(Debug)]
+struct PlannedCommand {
+    services: Vec<ServiceCode>,
+    elements: Vec<BlockElement>,
+    keys: Vec<(ServiceCode, u16)>,
+}
+
+/// Builder for a read spanning several services, e.g. blocks 0-3 of one
+/// service and block 0 of another.
+///
+/// Services are packed into as few commands as possible: up to 16
+/// services share one command, and each command is further split only
+/// when the card cannot return that many blocks at once.
+#[derive(Debug, Clone, Default)]
+pub struct ReadPlan {
+    services: Vec<ServiceCode>,
+    elements: Vec<(ServiceCode, u16)>,
+}
+
+impl ReadPlan {
+    /// Empty plan
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the blocks in `range` of `service`.
+    pub fn blocks(mut self, service: ServiceCode, range: Range<u16>) -> Self {
+        for block in range {
+            self = self.block(service, block);
+        }
+        self
+    }
+
+    /// Add a single block of `service`. Repeated blocks are read once.
+    pub fn block(mut self, service: ServiceCode, block: u16) -> Self {
+        if !self.services.contains(&service) {
+            self.services.push(service);
+        }
+        if !self.elements.contains(&(service, block)) {
+            self.elements.push((service, block));
+        }
+        self
+    }
+
+    /// Services in the order they were first added
+    pub fn services(&self) -> &[ServiceCode] {
+        &self.services
+    }
+
+    /// Number of blocks the plan reads
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Whether the plan reads no blocks
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Split the plan into commands of at most 16 services each.
+    fn commands(&self) -> Vec<PlannedCommand> {
+        self.services
+            .chunks(crate::constants::FELICA_MAX_SERVICES)
+            .map(|group| {
+                let keys: Vec<_> = self
+                    .elements
+                    .iter()
+                    .filter(|(svc, _)| group.contains(svc))
+                    .copied()
+                    .collect();
+                let elements = keys
+                    .iter()
+                    .map(|(svc, block)| {
+                        let index = group.iter().position(|s| s == svc).unwrap_or(0);
+                        BlockElement::new(index as u8, AccessMode::Normal, *block)
+                    })
+                    .collect();
+                PlannedCommand {
+                    services: group.to_vec(),
+                    elements,
+                    keys,
+                }
+            })
+            .collect()
+    }
+
+    /// Execute the plan against `card`.
+    pub fn read(
+        &self,
+        card: &crate::card::Card,
+        device: &mut Device<crate::device::Initialized>,
+    ) -> Result<PlanResult> {
+        let mut out = HashMap::with_capacity(self.elements.len());
+        for PlannedCommand {
+            services,
+            elements,
+            keys,
+        } in self.commands()
+        {
+            let blocks = super::read::read_blocks(card, device, &services, &elements)?;
+            if blocks.len() != keys.len() {
+                return Err(Error::InvalidLength {
+                    expected: keys.len(),
+                    actual: blocks.len(),
+                });
+            }
+            out.extend(keys.into_iter().zip(blocks));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, mock_device, read_fill_frame, test_card};
+
+    #[test]
+    fn plan_assigns_service_indexes() {
+        let a = ServiceCode::new(0x090f);
+        let b = ServiceCode::new(0x108f);
+        let plan = ReadPlan::new().blocks(a, 0..2).block(b, 0).block(a, 1);
+
+        assert_eq!(plan.services(), &[a, b]);
+        assert_eq!(plan.len(), 3);
+
+        let commands = plan.commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].services, vec![a, b]);
+        assert_eq!(
+            commands[0]
+                .elements
+                .iter()
+                .map(|e| e.service_index)
+                .collect::<Vec<_>>(),
+            vec![0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn plan_splits_after_sixteen_services() {
+        let plan = (0..17u16).fold(ReadPlan::new(), |plan, n| {
+            plan.block(ServiceCode::new((n << 6) | 0x0b), 0)
+        });
+        let commands = plan.commands();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].services.len(), 16);
+        assert_eq!(commands[1].elements[0].service_index, 0);
+    }
+
+    #[test]
+    fn plan_maps_blocks_to_sources() {
+        let mut dev = mock_device(vec![read_fill_frame(TEST_IDM, &[0xA0, 0xA1, 0xB0])]);
+        let card = test_card();
+
+        let a = ServiceCode::new(0x090f);
+        let b = ServiceCode::new(0x108f);
+        let result = ReadPlan::new()
+            .blocks(a, 0..2)
+            .block(b, 0)
+            .read(&card, &mut dev)
+            .unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[&(a, 1)].as_bytes(), &[0xA1; 16]);
+        assert_eq!(result[&(b, 0)].as_bytes(), &[0xB0; 16]);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::Result;
+use crate::device::Device;
+use crate::types::{BlockData, ServiceCode, ServiceType};
+
+/// Decoded block of a purse service.
+///
+/// Layout (little-endian): purse data(4) + cashback data(4) +
+/// user data(6) + execution ID(2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurseBlock {
+    /// Current balance (purse data)
+    pub balance: u32,
+    /// Cashback data of the last cashback access
+    pub cashback: u32,
+    /// Free-form user data
+    pub user_data: [u8; 6],
+    /// Execution ID, incremented by the card on every purse write
+    pub execution_id: u16,
+}
+
+impl From<&BlockData> for PurseBlock {
+    fn from(block: &BlockData) -> Self {
+        let b = block.as_bytes();
+        let mut user_data = [0u8; 6];
+        user_data.copy_from_slice(&b[8..14]);
+        Self {
+            balance: u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            cashback: u32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+            user_data,
+            execution_id: u16::from_le_bytes([b[14], b[15]]),
+        }
+    }
+}
+
+impl From<BlockData> for PurseBlock {
+    fn from(block: BlockData) -> Self {
+        Self::from(&block)
+    }
+}
+
+/// Read and decode the block of a purse service.
+pub fn read_purse(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+) -> Result<PurseBlock> {
+    super::ensure_service_type(service, ServiceType::Purse)?;
+    let block = super::read::read_single(card, device, service, 0)?;
+    Ok(PurseBlock::from(&block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, mock_device, read_frame, test_card};
+
+    #[test]
+    fn purse_block_decodes_fields() {
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&12_345u32.to_le_bytes());
+        bytes[4..8].copy_from_slice(&500u32.to_le_bytes());
+        bytes[8..14].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        bytes[14..16].copy_from_slice(&0x0102u16.to_le_bytes());
+
+        let purse = PurseBlock::from(BlockData::from_bytes(bytes));
+        assert_eq!(purse.balance, 12_345);
+        assert_eq!(purse.cashback, 500);
+        assert_eq!(purse.user_data, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(purse.execution_id, 0x0102);
+    }
+
+    #[test]
+    fn read_purse_via_device() {
+        let mut block = [0u8; 16];
+        block[0..4].copy_from_slice(&1_000u32.to_le_bytes());
+        let mut dev = mock_device(vec![read_frame(TEST_IDM, &[block])]);
+        let card = test_card();
+
+        // 0x1017: purse, read-only, no authentication
+        let purse = read_purse(&card, &mut dev, ServiceCode::new(0x1017)).unwrap();
+        assert_eq!(purse.balance, 1_000);
+    }
+
+    #[test]
+    fn read_purse_rejects_non_purse_service() {
+        let mut dev = mock_device(vec![]);
+        let card = test_card();
+
+        match read_purse(&card, &mut dev, ServiceCode::new(0x090f)) {
+            Err(crate::Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::device::Device;
-use crate::protocol::{Command, Response};
-use crate::types::{BlockData, BlockElement, ServiceCode};
+use crate::protocol::{BlockInformation, Command, Response};
+use crate::types::{BlockData, BlockElement, CommandClass, ServiceCode};
 use crate::{Error, Result};
 
 /// Read multiple blocks from a card using ReadWithoutEncryption.
+///
+/// Requests larger than the card accepts are split into several commands;
+/// blocks are returned in request order.
 pub fn read_blocks(
     card: &crate::card::Card,
     device: &mut Device<crate::device::Initialized>,
@@ -13,19 +16,55 @@ pub fn read_blocks(
     let idm = card.idm().ok_or_else(|| {
         Error::UnsupportedOperation("Card does not have IDm (not a FeliCa card)".into())
     })?;
+    super::ensure_unauthenticated_access(services)?;
 
+    super::chunk::run_chunked(card, device, CommandClass::Read, blocks, |device, chunk| {
+        read_blocks_once(card, device, *idm, services, chunk)
+    })
+}
+
+/// Send a single ReadWithoutEncryption command.
+fn read_blocks_once(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    idm: crate::types::Idm,
+    services: &[ServiceCode],
+    blocks: &[BlockElement],
+) -> Result<Vec<BlockData>> {
+    read_exchange(card, idm, services, blocks).run(device)
+}
+
+/// ReadWithoutEncryption for one chunk of blocks.
+pub(crate) fn read_exchange(
+    card: &crate::card::Card,
+    idm: crate::types::Idm,
+    services: &[ServiceCode],
+    blocks: &[BlockElement],
+) -> super::Exchange<Vec<BlockData>> {
     let cmd = Command::ReadWithoutEncryption {
-        idm: *idm,
+        idm,
         services: services.to_vec(),
         blocks: blocks.to_vec(),
     };
-
-    let resp = device.execute(cmd, 1000)?;
-
-    match resp {
-        Response::ReadWithoutEncryption { blocks, .. } => Ok(blocks),
-        _ => Err(Error::PollingFailed),
-    }
+    super::Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Read,
+        blocks.len(),
+        |_, resp| match resp {
+            Response::ReadWithoutEncryption {
+                status: (0, 0),
+                blocks,
+                ..
+            } => Ok(blocks),
+            Response::ReadWithoutEncryption {
+                status: (status1, status2),
+                ..
+            } => Err(Error::from_status(status1, status2)),
+            _ => Err(Error::PollingFailed),
+        },
+    )
 }
 
 /// Convenience helper that reads a single block.
@@ -41,10 +80,115 @@ pub fn read_single(
         &[service],
         &[BlockElement::new(
             0,
-            crate::types::AccessMode::DirectAccessOrRead,
+            crate::types::AccessMode::Normal,
             block,
         )],
     )?;
 
     blocks.into_iter().next().ok_or(Error::PollingFailed)
 }
+
+/// Read every block of a service. The block count is obtained with
+/// RequestBlockInformationEx, falling back to RequestBlockInformation for
+/// cards that do not implement the extended command.
+pub fn read_service_all(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+) -> Result<Vec<BlockData>> {
+    super::ensure_unauthenticated_access(&[service])?;
+    let count = service_block_count(card, device, service)? as usize;
+    read_block_range(card, device, service, count)
+}
+
+/// Read blocks `0..count` of a single service, preserving block order.
+pub(crate) fn read_block_range(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+    count: usize,
+) -> Result<Vec<BlockData>> {
+    read_blocks(card, device, &[service], &block_range(count))
+}
+
+/// Read elements for blocks `0..count` of the first service.
+pub(crate) fn block_range(count: usize) -> Vec<BlockElement> {
+    (0..count)
+        .map(|b| BlockElement::new(0, crate::types::AccessMode::Normal, b as u16))
+        .collect()
+}
+
+/// Number of blocks assigned to a service.
+pub(crate) fn service_block_count(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+) -> Result<u16> {
+    BlockCountQuery::new(card, service)?.run(device)
+}
+
+/// Assigned block count RequestBlockInformation(Ex) reports for a node the
+/// card does not have.
+const ABSENT_NODE: u16 = 0xFFFF;
+
+/// The exchanges that size a service: RequestBlockInformationEx, then
+/// RequestBlockInformation for cards without the extended command.
+pub(crate) struct BlockCountQuery {
+    service: ServiceCode,
+    ex: super::Exchange<Vec<BlockInformation>>,
+    plain: super::Exchange<Vec<u16>>,
+}
+
+impl BlockCountQuery {
+    pub(crate) fn new(card: &crate::card::Card, service: ServiceCode) -> Result<Self> {
+        let node = [service.as_u16()];
+        Ok(Self {
+            service,
+            ex: super::service::block_information_ex_exchange(card, &node)?,
+            plain: super::service::block_information_exchange(card, &node)?,
+        })
+    }
+
+    /// Run the query on a blocking device.
+    pub(crate) fn run(self, device: &mut Device<crate::device::Initialized>) -> Result<u16> {
+        let count = match self.ex.run(device) {
+            Ok(info) => info.first().map(|i| i.assigned_blocks),
+            Err(e) if super::block_information_ex_unsupported(&e) => {
+                self.plain.run(device)?.first().copied()
+            }
+            Err(e) => return Err(e),
+        };
+        assigned_blocks(self.service, count)
+    }
+
+    /// Run the query on an async device.
+    #[cfg(feature = "async")]
+    pub(crate) async fn run_async(
+        self,
+        device: &mut crate::device::AsyncDevice<crate::device::Initialized>,
+    ) -> Result<u16> {
+        let count = match self.ex.run_async(device).await {
+            Ok(info) => info.first().map(|i| i.assigned_blocks),
+            Err(e) if super::block_information_ex_unsupported(&e) => {
+                self.plain.run_async(device).await?.first().copied()
+            }
+            Err(e) => return Err(e),
+        };
+        assigned_blocks(self.service, count)
+    }
+}
+
+/// Check the block count the card reported for `service`.
+fn assigned_blocks(service: ServiceCode, count: Option<u16>) -> Result<u16> {
+    match count {
+        Some(ABSENT_NODE) => Err(Error::UnsupportedOperation(format!(
+            "service {:#06x} does not exist on the card",
+            service.as_u16()
+        ))),
+        Some(count) => Ok(count),
+        None => Err(Error::InvalidLength {
+            expected: 1,
+            actual: 0,
+        }),
+    }
+}
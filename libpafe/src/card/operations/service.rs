@@ -1,13 +1,19 @@
+use super::Exchange;
+use crate::card::tree::{AreaNode, build_tree};
 use crate::device::Device;
-use crate::protocol::{Command, Response};
-use crate::types::{Idm, SystemCode};
+use crate::protocol::{BlockInformation, Command, Response, SearchServiceEntry};
+use crate::types::{CommandClass, Idm, SystemCode};
 use crate::{Error, Result};
 
 const REQUEST_SERVICE_RSP: u8 = 0x03;
 const REQUEST_RESPONSE_RSP: u8 = 0x05;
 const REQUEST_SYSTEM_RSP: u8 = 0x0D;
+const REQUEST_BLOCK_INFO_RSP: u8 = 0x0F;
+const REQUEST_BLOCK_INFO_EX_RSP: u8 = 0x1F;
+const SEARCH_SERVICE_RSP: u8 = 0x0B;
 
-/// Iterator over service codes returned by SearchServiceCode.
+/// Iterator over the area and service codes returned by SearchServiceCode,
+/// in the card's node order. Use `discover_tree` to get the hierarchy.
 pub struct ServiceIterator<'a> {
     card: &'a crate::card::Card,
     device: &'a mut Device<crate::device::Initialized>,
@@ -35,26 +41,32 @@ pub fn request_service_versions(
     device: &mut Device<crate::device::Initialized>,
     node_codes: &[u16],
 ) -> Result<Vec<u16>> {
+    service_versions_exchange(card, node_codes)?.run(device)
+}
+
+pub(crate) fn service_versions_exchange(
+    card: &crate::card::Card,
+    node_codes: &[u16],
+) -> Result<Exchange<Vec<u16>>> {
     let idm = require_felica(card)?;
     let cmd = Command::RequestService {
         idm,
         node_codes: node_codes.to_vec(),
     };
-
-    match device.execute(cmd, 1000)? {
-        Response::RequestService {
-            idm: resp_idm,
-            versions,
-        } if resp_idm == idm => Ok(versions),
-        Response::RequestService { .. } => Err(Error::UnexpectedResponse {
-            expected: REQUEST_SERVICE_RSP,
-            actual: REQUEST_SERVICE_RSP,
-        }),
-        other => Err(Error::UnexpectedResponse {
-            expected: REQUEST_SERVICE_RSP,
-            actual: other.response_code(),
-        }),
-    }
+    Ok(Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::RequestService,
+        node_codes.len(),
+        |_, resp| match resp {
+            Response::RequestService { versions, .. } => Ok(versions),
+            other => Err(Error::UnexpectedResponse {
+                expected: REQUEST_SERVICE_RSP,
+                actual: other.response_code(),
+            }),
+        },
+    ))
 }
 
 /// Query the current operating mode via RequestResponse.
@@ -62,23 +74,26 @@ pub fn request_response_mode(
     card: &crate::card::Card,
     device: &mut Device<crate::device::Initialized>,
 ) -> Result<u8> {
+    response_mode_exchange(card)?.run(device)
+}
+
+pub(crate) fn response_mode_exchange(card: &crate::card::Card) -> Result<Exchange<u8>> {
     let idm = require_felica(card)?;
     let cmd = Command::RequestResponse { idm };
-
-    match device.execute(cmd, 1000)? {
-        Response::RequestResponse {
-            idm: resp_idm,
-            mode,
-        } if resp_idm == idm => Ok(mode),
-        Response::RequestResponse { .. } => Err(Error::UnexpectedResponse {
-            expected: REQUEST_RESPONSE_RSP,
-            actual: REQUEST_RESPONSE_RSP,
-        }),
-        other => Err(Error::UnexpectedResponse {
-            expected: REQUEST_RESPONSE_RSP,
-            actual: other.response_code(),
-        }),
-    }
+    Ok(Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::RequestResponse,
+        0,
+        |_, resp| match resp {
+            Response::RequestResponse { mode, .. } => Ok(mode),
+            other => Err(Error::UnexpectedResponse {
+                expected: REQUEST_RESPONSE_RSP,
+                actual: other.response_code(),
+            }),
+        },
+    ))
 }
 
 /// Retrieve the list of published system codes for the card.
@@ -86,23 +101,97 @@ pub fn request_system_codes(
     card: &crate::card::Card,
     device: &mut Device<crate::device::Initialized>,
 ) -> Result<Vec<SystemCode>> {
+    system_codes_exchange(card)?.run(device)
+}
+
+pub(crate) fn system_codes_exchange(card: &crate::card::Card) -> Result<Exchange<Vec<SystemCode>>> {
     let idm = require_felica(card)?;
     let cmd = Command::RequestSystemCode { idm };
+    Ok(Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Other,
+        0,
+        |_, resp| match resp {
+            Response::RequestSystemCode { system_codes, .. } => Ok(system_codes),
+            other => Err(Error::UnexpectedResponse {
+                expected: REQUEST_SYSTEM_RSP,
+                actual: other.response_code(),
+            }),
+        },
+    ))
+}
 
-    match device.execute(cmd, 1000)? {
-        Response::RequestSystemCode {
-            idm: resp_idm,
-            system_codes,
-        } if resp_idm == idm => Ok(system_codes),
-        Response::RequestSystemCode { .. } => Err(Error::UnexpectedResponse {
-            expected: REQUEST_SYSTEM_RSP,
-            actual: REQUEST_SYSTEM_RSP,
-        }),
-        other => Err(Error::UnexpectedResponse {
-            expected: REQUEST_SYSTEM_RSP,
-            actual: other.response_code(),
-        }),
-    }
+/// Request the block counts for the provided nodes via
+/// RequestBlockInformation. Service nodes report their assigned blocks,
+/// area nodes their free blocks.
+pub fn request_block_information(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    node_codes: &[u16],
+) -> Result<Vec<u16>> {
+    block_information_exchange(card, node_codes)?.run(device)
+}
+
+pub(crate) fn block_information_exchange(
+    card: &crate::card::Card,
+    node_codes: &[u16],
+) -> Result<Exchange<Vec<u16>>> {
+    let idm = require_felica(card)?;
+    let cmd = Command::RequestBlockInformation {
+        idm,
+        node_codes: node_codes.to_vec(),
+    };
+    Ok(Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Other,
+        node_codes.len(),
+        |_, resp| match resp {
+            Response::RequestBlockInformation { block_counts, .. } => Ok(block_counts),
+            other => Err(Error::UnexpectedResponse {
+                expected: REQUEST_BLOCK_INFO_RSP,
+                actual: other.response_code(),
+            }),
+        },
+    ))
+}
+
+/// Request assigned and free block counts for the provided nodes via
+/// RequestBlockInformationEx.
+pub fn request_block_information_ex(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    node_codes: &[u16],
+) -> Result<Vec<BlockInformation>> {
+    block_information_ex_exchange(card, node_codes)?.run(device)
+}
+
+pub(crate) fn block_information_ex_exchange(
+    card: &crate::card::Card,
+    node_codes: &[u16],
+) -> Result<Exchange<Vec<BlockInformation>>> {
+    let idm = require_felica(card)?;
+    let cmd = Command::RequestBlockInformationEx {
+        idm,
+        node_codes: node_codes.to_vec(),
+    };
+    Ok(Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Other,
+        node_codes.len(),
+        |_, resp| match resp {
+            Response::RequestBlockInformationEx { block_info, .. } => Ok(block_info),
+            other => Err(Error::UnexpectedResponse {
+                expected: REQUEST_BLOCK_INFO_EX_RSP,
+                actual: other.response_code(),
+            }),
+        },
+    ))
 }
 
 fn require_felica(card: &crate::card::Card) -> Result<Idm> {
@@ -111,6 +200,44 @@ fn require_felica(card: &crate::card::Card) -> Result<Idm> {
     })
 }
 
+/// Fetch the SearchServiceCode entry at `index`; `None` marks the end of
+/// the node list.
+fn search_service_entry(
+    idm: Idm,
+    device: &mut Device<crate::device::Initialized>,
+    index: u16,
+    timeout_ms: u64,
+) -> Result<Option<SearchServiceEntry>> {
+    let cmd = Command::SearchServiceCode { idm, index };
+    match device.execute(cmd, timeout_ms)? {
+        Response::SearchServiceCode { entry, .. } => Ok(entry),
+        other => Err(Error::UnexpectedResponse {
+            expected: SEARCH_SERVICE_RSP,
+            actual: other.response_code(),
+        }),
+    }
+}
+
+/// Walk every node with SearchServiceCode and rebuild the card's
+/// area/service hierarchy.
+pub fn discover_tree(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+) -> Result<AreaNode> {
+    let idm = require_felica(card)?;
+    let timeout_ms = super::command_timeout(card, CommandClass::Other, 0);
+
+    let mut entries = Vec::new();
+    for index in 0..=u16::MAX {
+        match search_service_entry(idm, device, index, timeout_ms)? {
+            Some(entry) => entries.push(entry),
+            None => break,
+        }
+    }
+
+    Ok(build_tree(&entries))
+}
+
 impl<'a> Iterator for ServiceIterator<'a> {
     type Item = Result<u16>;
 
@@ -129,36 +256,22 @@ impl<'a> Iterator for ServiceIterator<'a> {
             }
         };
 
-        let cmd = crate::protocol::Command::SearchServiceCode {
-            idm,
-            index: self.current_index,
-        };
-
-        match self.device.execute(cmd, 1000) {
-            Ok(crate::protocol::Response::SearchServiceCode {
-                area_or_service_code: Some(code),
-                ..
-            }) => {
-                // move to next index and yield code
+        let timeout_ms = super::command_timeout(self.card, CommandClass::Other, 0);
+        match search_service_entry(idm, self.device, self.current_index, timeout_ms) {
+            Ok(Some(entry)) => {
+                // move to next index and yield code; index 0xFFFF is the
+                // last one the command can address
+                if self.current_index == u16::MAX {
+                    self.finished = true;
+                }
                 self.current_index = self.current_index.saturating_add(1);
-                Some(Ok(code))
+                Some(Ok(entry.code()))
             }
-            Ok(crate::protocol::Response::SearchServiceCode {
-                area_or_service_code: None,
-                ..
-            }) => {
+            Ok(None) => {
                 // termination
                 self.finished = true;
                 None
             }
-            Ok(_) => {
-                // unexpected response
-                self.finished = true;
-                Some(Err(crate::Error::UnexpectedResponse {
-                    expected: 0x0b,
-                    actual: 0x00,
-                }))
-            }
             Err(e) => {
                 self.finished = true;
                 Some(Err(e))
@@ -3,7 +3,7 @@
 use crate::device::Device;
 use crate::protocol::Command;
 use crate::protocol::Response;
-use crate::types::{BlockData, BlockElement, ServiceCode};
+use crate::types::{BlockData, BlockElement, CommandClass, ServiceCode};
 use crate::{Error, Result};
 
 /// Write a single block to the card using WriteWithoutEncryption.
@@ -14,39 +14,40 @@ pub fn write_single(
     block: BlockElement,
     data: BlockData,
 ) -> Result<()> {
+    write_single_exchange(card, service, block, data)?.run(device)
+}
+
+/// WriteWithoutEncryption for a single block.
+pub(crate) fn write_single_exchange(
+    card: &crate::card::Card,
+    service: ServiceCode,
+    block: BlockElement,
+    data: BlockData,
+) -> Result<super::Exchange<()>> {
+    super::ensure_unauthenticated_access(&[service])?;
+    let idm = *card
+        .idm()
+        .ok_or_else(|| Error::UnsupportedOperation("Card does not have IDm".into()))?;
     let cmd = Command::WriteWithoutEncryption {
-        idm: *card
-            .idm()
-            .ok_or_else(|| Error::UnsupportedOperation("Card does not have IDm".into()))?,
+        idm,
         service,
         block,
         data,
     };
-
-    let resp = device.execute(cmd, 1000)?;
-    match resp {
-        Response::WriteWithoutEncryption { statuses, .. } => {
-            if statuses.is_empty() {
-                return Err(Error::InvalidLength {
-                    expected: 11,
-                    actual: 0,
-                });
-            }
-            let status = statuses[0];
-            if status.0 != 0 || status.1 != 0 {
-                Err(Error::FelicaStatus {
-                    status1: status.0,
-                    status2: status.1,
-                })
-            } else {
-                Ok(())
-            }
-        }
-        _ => Err(Error::PollingFailed),
-    }
+    Ok(super::Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Write,
+        1,
+        |_, resp| write_status(resp),
+    ))
 }
 
-/// Write multiple blocks in a single WriteWithoutEncryption command.
+/// Write multiple blocks with WriteWithoutEncryption, splitting the request
+/// into as many commands as the card needs. A failure after the first
+/// command is reported as `Error::ChunkFailed`; blocks before its `start`
+/// have been written.
 pub fn write_blocks(
     card: &crate::card::Card,
     device: &mut Device<crate::device::Initialized>,
@@ -56,21 +57,164 @@ pub fn write_blocks(
     if blocks.is_empty() {
         return Ok(());
     }
+    super::ensure_unauthenticated_access(&[service])?;
+
+    let idm = *card
+        .idm()
+        .ok_or_else(|| Error::UnsupportedOperation("Card does not have IDm".into()))?;
+
+    super::chunk::run_chunked(
+        card,
+        device,
+        CommandClass::Write,
+        blocks,
+        |device, chunk| {
+            write_blocks_once(card, device, idm, service, chunk)?;
+            Ok(Vec::<()>::new())
+        },
+    )?;
+    Ok(())
+}
+
+/// How the `*_with_options` write APIs confirm a write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Read the written blocks back and compare them with the data sent.
+    pub verify: bool,
+    /// Extra attempts for blocks that failed to write or verify.
+    pub retries: u8,
+}
+
+/// Outcome of a write made with `WriteOptions`. Both lists keep the
+/// order of the original request.
+#[derive(Debug)]
+pub struct WriteReport {
+    /// Blocks that were written (and, when verifying, read back intact).
+    pub succeeded: Vec<BlockElement>,
+    /// Blocks still failing after the last attempt.
+    pub failed: Vec<BlockElement>,
+    /// The error from the last failed write or readback, if any.
+    pub last_error: Option<Error>,
+}
+
+impl WriteReport {
+    /// Whether every requested block was written.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Write blocks like [`write_blocks`], then optionally read them back and
+/// retry only the blocks that did not land.
+///
+/// Errors that make the request impossible (no IDm, a service requiring
+/// authentication) are returned directly; failures during the attempts
+/// end up in the report.
+pub fn write_blocks_with_options(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    service: ServiceCode,
+    blocks: &[(BlockElement, BlockData)],
+    options: WriteOptions,
+) -> Result<WriteReport> {
+    super::ensure_unauthenticated_access(&[service])?;
+    if card.idm().is_none() {
+        return Err(Error::UnsupportedOperation("Card does not have IDm".into()));
+    }
+
+    // Indexes into `blocks` that have not been confirmed yet
+    let mut pending: Vec<usize> = (0..blocks.len()).collect();
+    let mut last_error = None;
+
+    for _ in 0..=options.retries {
+        if pending.is_empty() {
+            break;
+        }
+        let batch: Vec<(BlockElement, BlockData)> = pending.iter().map(|&i| blocks[i]).collect();
+        let written = write_blocks(card, device, service, &batch);
+
+        if options.verify {
+            if let Err(e) = written {
+                last_error = Some(e);
+            }
+            let elements: Vec<BlockElement> = batch.iter().map(|(b, _)| *b).collect();
+            match super::read_blocks(card, device, &[service], &elements) {
+                Ok(readback) => {
+                    if readback.len() != batch.len() {
+                        last_error = Some(Error::InvalidLength {
+                            expected: batch.len(),
+                            actual: readback.len(),
+                        });
+                    }
+                    // Blocks missing from a short readback stay unverified
+                    pending = pending
+                        .into_iter()
+                        .enumerate()
+                        .filter(|&(n, i)| readback.get(n) != Some(&blocks[i].1))
+                        .map(|(_, i)| i)
+                        .collect();
+                }
+                Err(e) => last_error = Some(e),
+            }
+        } else {
+            match written {
+                Ok(()) => pending.clear(),
+                Err(e) => {
+                    // Commands before the failing chunk were acknowledged
+                    if let Error::ChunkFailed { start, .. } = e {
+                        pending.drain(..start);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+    }
+
+    let (failed, succeeded): (Vec<usize>, Vec<usize>) =
+        (0..blocks.len()).partition(|i| pending.contains(i));
+    Ok(WriteReport {
+        succeeded: succeeded.into_iter().map(|i| blocks[i].0).collect(),
+        failed: failed.into_iter().map(|i| blocks[i].0).collect(),
+        last_error: if pending.is_empty() { None } else { last_error },
+    })
+}
 
-    let services = vec![service];
-    let block_elems: Vec<_> = blocks.iter().map(|(b, _)| *b).collect();
-    let data_blocks: Vec<_> = blocks.iter().map(|(_, d)| *d).collect();
+/// Send a single WriteWithoutEncryption command.
+fn write_blocks_once(
+    card: &crate::card::Card,
+    device: &mut Device<crate::device::Initialized>,
+    idm: crate::types::Idm,
+    service: ServiceCode,
+    blocks: &[(BlockElement, BlockData)],
+) -> Result<()> {
+    write_multi_exchange(card, idm, service, blocks).run(device)
+}
 
+/// WriteWithoutEncryption for one chunk of blocks of a single service.
+pub(crate) fn write_multi_exchange(
+    card: &crate::card::Card,
+    idm: crate::types::Idm,
+    service: ServiceCode,
+    blocks: &[(BlockElement, BlockData)],
+) -> super::Exchange<()> {
     let cmd = crate::protocol::commands::Command::WriteWithoutEncryptionMulti {
-        idm: *card
-            .idm()
-            .ok_or_else(|| Error::UnsupportedOperation("Card does not have IDm".into()))?,
-        services,
-        blocks: block_elems,
-        data: data_blocks,
+        idm,
+        services: vec![service],
+        blocks: blocks.iter().map(|(b, _)| *b).collect(),
+        data: blocks.iter().map(|(_, d)| *d).collect(),
     };
+    super::Exchange::new(
+        card,
+        idm,
+        cmd,
+        CommandClass::Write,
+        blocks.len(),
+        |_, resp| write_status(resp),
+    )
+}
 
-    let resp = device.execute(cmd, 1000)?;
+/// Interpret a WriteWithoutEncryption response.
+fn write_status(resp: Response) -> Result<()> {
     match resp {
         Response::WriteWithoutEncryption { statuses, .. } => {
             if statuses.is_empty() {
@@ -82,10 +226,7 @@ pub fn write_blocks(
             // For now, treat the first status as the overall operation status
             let status = statuses[0];
             if status.0 != 0 || status.1 != 0 {
-                Err(Error::FelicaStatus {
-                    status1: status.0,
-                    status2: status.1,
-                })
+                Err(Error::from_status(status.0, status.1))
             } else {
                 Ok(())
             }
@@ -100,6 +241,7 @@ mod tests {
     use crate::card::Card;
     use crate::device::Device;
     use crate::protocol::Frame;
+    use crate::test_support::{TEST_IDM, mock_device, read_fill_frame, status_frame, test_card};
     use crate::transport::mock::MockTransport;
     use crate::types::DeviceType;
     use crate::types::{BlockData, BlockElement, Idm, Pmm, ServiceCode, SystemCode};
@@ -135,7 +277,7 @@ mod tests {
 
         // Perform write
         let svc = ServiceCode::new(0x090f);
-        let blk = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, 0x0012);
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0012);
         let data = BlockData::from_bytes([0x5A; 16]);
         write_single(&card, &mut dev, svc, blk, data).unwrap();
     }
@@ -164,7 +306,7 @@ mod tests {
         );
 
         let svc = ServiceCode::new(0x090f);
-        let blk = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, 0x0012);
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0012);
         let data = BlockData::from_bytes([0x00; 16]);
 
         match write_single(&card, &mut dev, svc, blk, data) {
@@ -199,8 +341,8 @@ mod tests {
         );
 
         let svc = ServiceCode::new(0x090f);
-        let blk1 = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, 0x0012);
-        let blk2 = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, 0x0013);
+        let blk1 = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0012);
+        let blk2 = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0013);
         let data1 = BlockData::from_bytes([0x01; 16]);
         let data2 = BlockData::from_bytes([0x02; 16]);
 
@@ -230,7 +372,7 @@ mod tests {
         );
 
         let svc = ServiceCode::new(0x090f);
-        let blk = BlockElement::new(0, crate::types::AccessMode::DirectAccessOrRead, 0x0012);
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0012);
         let data = BlockData::from_bytes([0x00; 16]);
 
         match write_blocks(&card, &mut dev, svc, &[(blk, data)]) {
@@ -241,4 +383,122 @@ mod tests {
             other => panic!("expected FelicaStatus, got {:?}", other),
         }
     }
+
+    #[test]
+    fn write_single_rejects_service_requiring_authentication() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        mock.push_response(vec![0xAA]);
+
+        let boxed: Box<dyn crate::transport::traits::Transport> = Box::new(mock);
+        let device = Device::new_with_transport(boxed).unwrap();
+        let mut dev = device.initialize().unwrap();
+
+        let card = Card::new(
+            Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            Pmm::from_bytes([0; 8]),
+            SystemCode::new(0x0003),
+        );
+
+        // 0x1008: random read/write service that requires authentication
+        let svc = ServiceCode::new(0x1008);
+        let blk = BlockElement::new(0, crate::types::AccessMode::Normal, 0x0000);
+        let data = BlockData::from_bytes([0x00; 16]);
+
+        match write_single(&card, &mut dev, svc, blk, data) {
+            Err(Error::UnsupportedOperation(msg)) => assert!(msg.contains("0x1008")),
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+    }
+
+    fn write_frame(status1: u8) -> Vec<u8> {
+        status_frame(0x09, TEST_IDM, status1, 0)
+    }
+
+    fn two_blocks() -> [(BlockElement, BlockData); 2] {
+        let mode = crate::types::AccessMode::Normal;
+        [
+            (
+                BlockElement::new(0, mode, 0),
+                BlockData::from_bytes([0x11; 16]),
+            ),
+            (
+                BlockElement::new(0, mode, 1),
+                BlockData::from_bytes([0x22; 16]),
+            ),
+        ]
+    }
+
+    #[test]
+    fn write_with_verify_retries_only_mismatched_blocks() {
+        let card = test_card();
+        let mut dev = mock_device(vec![
+            write_frame(0),
+            read_fill_frame(TEST_IDM, &[0x11, 0x00]), // block 1 did not land
+            write_frame(0),
+            read_fill_frame(TEST_IDM, &[0x22]),
+        ]);
+        let blocks = two_blocks();
+        let options = WriteOptions {
+            verify: true,
+            retries: 1,
+        };
+
+        let report =
+            write_blocks_with_options(&card, &mut dev, ServiceCode::new(0x0009), &blocks, options)
+                .unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.succeeded, vec![blocks[0].0, blocks[1].0]);
+        assert!(report.last_error.is_none());
+    }
+
+    #[test]
+    fn write_with_verify_reports_blocks_that_never_land() {
+        let card = test_card();
+        let mut dev = mock_device(vec![
+            write_frame(0xA4),
+            read_fill_frame(TEST_IDM, &[0x11, 0x00]),
+        ]);
+        let blocks = two_blocks();
+        let options = WriteOptions {
+            verify: true,
+            retries: 0,
+        };
+
+        let report =
+            write_blocks_with_options(&card, &mut dev, ServiceCode::new(0x0009), &blocks, options)
+                .unwrap();
+        assert_eq!(report.succeeded, vec![blocks[0].0]);
+        assert_eq!(report.failed, vec![blocks[1].0]);
+        assert!(matches!(
+            report.last_error,
+            Some(Error::FelicaStatus { status1: 0xA4, .. })
+        ));
+    }
+
+    #[test]
+    fn write_with_verify_keeps_blocks_missing_from_readback() {
+        let card = test_card();
+        let mut dev = mock_device(vec![
+            write_frame(0),
+            read_fill_frame(TEST_IDM, &[0x11]), // only one of two blocks
+        ]);
+        let blocks = two_blocks();
+        let options = WriteOptions {
+            verify: true,
+            retries: 0,
+        };
+
+        let report =
+            write_blocks_with_options(&card, &mut dev, ServiceCode::new(0x0009), &blocks, options)
+                .unwrap();
+        assert_eq!(report.succeeded, vec![blocks[0].0]);
+        assert_eq!(report.failed, vec![blocks[1].0]);
+        assert!(matches!(
+            report.last_error,
+            Some(Error::InvalidLength {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
 }
@@ -0,0 +1,216 @@
+use crate::device::{Device, Initialized};
+use crate::types::{BlockData, BlockElement, Idm, ServiceCode, SystemCode};
+use crate::{Error, Result};
+
+use super::operations::{
+    Journal, PlanResult, PurseBlock, ReadPlan, RecoveryOutcome, ServiceIterator, WriteOptions,
+    WriteReport,
+};
+use super::{AreaNode, Card};
+
+/// A FeliCa card bound to the device it was detected on.
+///
+/// The session borrows the device mutably for its whole lifetime, so no
+/// other card can be addressed through it in the meantime. Every command
+/// it sends carries the session's IDm, and `Device::execute` rejects
+/// responses from any other card with `Error::IdmMismatch`.
+pub struct CardSession<'d> {
+    device: &'d mut Device<Initialized>,
+    card: Card,
+    idm: Idm,
+}
+
+impl<'d> CardSession<'d> {
+    /// Open a session for `card` on `device`. Only FeliCa cards (which
+    /// have an IDm) are supported.
+    pub fn new(device: &'d mut Device<Initialized>, card: Card) -> Result<Self> {
+        let idm = *card.idm().ok_or_else(|| {
+            Error::UnsupportedOperation(
+                "card sessions are only supported for FeliCa (Type F) cards".into(),
+            )
+        })?;
+        Ok(Self { device, card, idm })
+    }
+
+    /// The card this session addresses.
+    pub fn card(&self) -> &Card {
+        &self.card
+    }
+
+    /// IDm every response must carry.
+    pub fn idm(&self) -> Idm {
+        self.idm
+    }
+
+    /// Close the session and return the card.
+    pub fn into_card(self) -> Card {
+        self.card
+    }
+
+    /// Read blocks using ReadWithoutEncryption.
+    pub fn read_blocks(
+        &mut self,
+        services: &[ServiceCode],
+        blocks: &[BlockElement],
+    ) -> Result<Vec<BlockData>> {
+        self.card.read_blocks(self.device, services, blocks)
+    }
+
+    /// Read a single block.
+    pub fn read_single(&mut self, service: ServiceCode, block: u16) -> Result<BlockData> {
+        self.card.read_single(self.device, service, block)
+    }
+
+    /// Read every block of a service.
+    pub fn read_service_all(&mut self, service: ServiceCode) -> Result<Vec<BlockData>> {
+        self.card.read_service_all(self.device, service)
+    }
+
+    /// Read the blocks of a multi-service `ReadPlan`.
+    pub fn read_plan(&mut self, plan: &ReadPlan) -> Result<PlanResult> {
+        self.card.read_plan(self.device, plan)
+    }
+
+    /// Read and decode the block of a purse service.
+    pub fn read_purse(&mut self, service: ServiceCode) -> Result<PurseBlock> {
+        self.card.read_purse(self.device, service)
+    }
+
+    /// Read the latest `count` records of a cyclic service, oldest first.
+    pub fn read_cyclic_latest(
+        &mut self,
+        service: ServiceCode,
+        count: usize,
+    ) -> Result<Vec<BlockData>> {
+        self.card.read_cyclic_latest(self.device, service, count)
+    }
+
+    /// Write a single block using WriteWithoutEncryption.
+    pub fn write_single(
+        &mut self,
+        service: ServiceCode,
+        block: u16,
+        data: BlockData,
+    ) -> Result<()> {
+        self.card.write_single(self.device, service, block, data)
+    }
+
+    /// Write multiple blocks using WriteWithoutEncryption.
+    pub fn write_blocks(
+        &mut self,
+        service: ServiceCode,
+        blocks: &[(BlockElement, BlockData)],
+    ) -> Result<()> {
+        self.card.write_blocks(self.device, service, blocks)
+    }
+
+    /// Write multiple blocks with optional readback verification and retries.
+    pub fn write_blocks_with_options(
+        &mut self,
+        service: ServiceCode,
+        blocks: &[(BlockElement, BlockData)],
+        options: WriteOptions,
+    ) -> Result<WriteReport> {
+        self.card
+            .write_blocks_with_options(self.device, service, blocks, options)
+    }
+
+    /// Write blocks of `service` as one transaction staged in `journal`.
+    pub fn write_journaled(
+        &mut self,
+        journal: &Journal,
+        service: ServiceCode,
+        blocks: &[(u16, BlockData)],
+    ) -> Result<()> {
+        self.card
+            .write_journaled(self.device, journal, service, blocks)
+    }
+
+    /// Finish or roll back an interrupted journaled write.
+    pub fn recover(&mut self, journal: &Journal) -> Result<RecoveryOutcome> {
+        self.card.recover(self.device, journal)
+    }
+
+    /// Iterate over service codes found by SearchServiceCode.
+    pub fn services(&mut self) -> ServiceIterator<'_> {
+        self.card.services(self.device)
+    }
+
+    /// Return the card's area/service hierarchy.
+    pub fn discover_tree(&mut self) -> Result<AreaNode> {
+        self.card.discover_tree(self.device)
+    }
+
+    /// Request key versions for the provided node codes.
+    pub fn request_service_versions(&mut self, node_codes: &[u16]) -> Result<Vec<u16>> {
+        self.card.request_service_versions(self.device, node_codes)
+    }
+
+    /// Query the card's current operating mode.
+    pub fn request_response_mode(&mut self) -> Result<u8> {
+        self.card.request_response_mode(self.device)
+    }
+
+    /// Request the list of published system codes.
+    pub fn request_system_codes(&mut self) -> Result<Vec<SystemCode>> {
+        self.card.request_system_codes(self.device)
+    }
+
+    /// Request block counts for the provided node codes.
+    pub fn request_block_information(&mut self, node_codes: &[u16]) -> Result<Vec<u16>> {
+        self.card.request_block_information(self.device, node_codes)
+    }
+
+    /// Request assigned/free block counts for the provided node codes.
+    pub fn request_block_information_ex(
+        &mut self,
+        node_codes: &[u16],
+    ) -> Result<Vec<crate::protocol::BlockInformation>> {
+        self.card
+            .request_block_information_ex(self.device, node_codes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_device, polling_frame, response_mode_frame};
+    use crate::types::Pmm;
+
+    #[test]
+    fn polling_session_binds_polled_card() {
+        let idm = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut dev = mock_device(vec![polling_frame(idm), response_mode_frame(idm)]);
+        let mut session = dev.polling_session(SystemCode::new(0x0003)).unwrap();
+        assert_eq!(session.idm(), Idm::from_bytes(idm));
+        assert_eq!(session.request_response_mode().unwrap(), 0);
+    }
+
+    #[test]
+    fn session_rejects_response_from_other_card() {
+        let mut dev = mock_device(vec![response_mode_frame([9; 8])]);
+        let card = Card::new(
+            Idm::from_bytes([1; 8]),
+            Pmm::from_bytes([0; 8]),
+            SystemCode::new(0x0003),
+        );
+        let mut session = CardSession::new(&mut dev, card).unwrap();
+        match session.request_response_mode() {
+            Err(Error::IdmMismatch { expected, actual }) => {
+                assert_eq!(expected, Idm::from_bytes([1; 8]));
+                assert_eq!(actual, Idm::from_bytes([9; 8]));
+            }
+            other => panic!("expected IdmMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn session_requires_felica_card() {
+        let mut dev = mock_device(vec![]);
+        let card = Card::new_type_a(crate::types::Uid::from_bytes(vec![1, 2, 3, 4]));
+        assert!(matches!(
+            CardSession::new(&mut dev, card),
+            Err(Error::UnsupportedOperation(_))
+        ));
+    }
+}
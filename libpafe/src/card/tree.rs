@@ -0,0 +1,173 @@
+// libpafe-rs/libpafe/src/card/tree.rs
+
+//! Area/service hierarchy rebuilt from SearchServiceCode results.
+//!
+//! SearchServiceCode walks the card's nodes in code order. Every area
+//! entry carries its end code, so the hierarchy can be rebuilt by
+//! nesting each node under the innermost open area whose number range
+//! (bits 15-6 of the code) contains it.
+
+use crate::protocol::SearchServiceEntry;
+use crate::types::ServiceCode;
+
+/// Area node with its code range and the nodes nested under it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AreaNode {
+    /// Area code (area number + attribute)
+    pub code: u16,
+    /// End code of the range covered by the area
+    pub end_code: u16,
+    /// Sub-areas in discovery order
+    pub areas: Vec<AreaNode>,
+    /// Services directly below this area in discovery order
+    pub services: Vec<ServiceNode>,
+}
+
+/// Service node discovered on the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceNode {
+    /// Service code (service number + attribute)
+    pub code: ServiceCode,
+}
+
+impl AreaNode {
+    /// Create an empty area covering `code..=end_code`.
+    pub fn new(code: u16, end_code: u16) -> Self {
+        Self {
+            code,
+            end_code,
+            areas: Vec::new(),
+            services: Vec::new(),
+        }
+    }
+
+    /// First area number of the range (bits 15-6 of the area code).
+    pub fn number(&self) -> u16 {
+        self.code >> 6
+    }
+
+    /// Last area number of the range (bits 15-6 of the end code).
+    pub fn end_number(&self) -> u16 {
+        self.end_code >> 6
+    }
+
+    /// Whether a node code falls inside this area's number range.
+    pub fn contains(&self, code: u16) -> bool {
+        (self.number()..=self.end_number()).contains(&(code >> 6))
+    }
+
+    /// All service codes below this area, depth-first in discovery order.
+    pub fn service_codes(&self) -> Vec<ServiceCode> {
+        let mut out: Vec<ServiceCode> = self.services.iter().map(|s| s.code).collect();
+        for area in &self.areas {
+            out.extend(area.service_codes());
+        }
+        out
+    }
+}
+
+/// Rebuild the area/service hierarchy from SearchServiceCode entries in
+/// discovery order. The first entry is used as the root when it is an
+/// area; otherwise a root covering the whole code space is synthesised.
+pub(crate) fn build_tree(entries: &[SearchServiceEntry]) -> AreaNode {
+    let mut rest = entries;
+    let root = match entries.first() {
+        Some(&SearchServiceEntry::Area { code, end_code }) => {
+            rest = &entries[1..];
+            AreaNode::new(code, end_code)
+        }
+        _ => AreaNode::new(0x0000, 0xFFFF),
+    };
+
+    // Stack of open areas, innermost last. The root is never popped so
+    // nodes outside every range still end up in the tree.
+    let mut stack = vec![root];
+    for entry in rest {
+        let code = entry.code();
+        while stack.len() > 1 && !stack[stack.len() - 1].contains(code) {
+            close_innermost(&mut stack);
+        }
+
+        match *entry {
+            SearchServiceEntry::Area { code, end_code } => {
+                stack.push(AreaNode::new(code, end_code));
+            }
+            SearchServiceEntry::Service(code) => {
+                let parent = stack.len() - 1;
+                stack[parent].services.push(ServiceNode {
+                    code: ServiceCode::new(code),
+                });
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        close_innermost(&mut stack);
+    }
+    stack.swap_remove(0)
+}
+
+fn close_innermost(stack: &mut Vec<AreaNode>) {
+    if let Some(done) = stack.pop() {
+        let parent = stack.len() - 1;
+        stack[parent].areas.push(done);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_tree_nests_areas_by_range() {
+        use SearchServiceEntry::{Area, Service};
+
+        let entries = [
+            Area {
+                code: 0x0000,
+                end_code: 0xfffe,
+            },
+            Service(0x0009),
+            Area {
+                code: 0x1000,
+                end_code: 0x17ff,
+            },
+            Service(0x100b),
+            Area {
+                code: 0x1400,
+                end_code: 0x15ff,
+            },
+            Service(0x1408),
+            // Back in area 0x1000 once the sub-area range is exceeded
+            Service(0x1608),
+            // Back in the root area
+            Service(0x2009),
+        ];
+
+        let root = build_tree(&entries);
+        assert_eq!((root.code, root.end_code), (0x0000, 0xfffe));
+        assert_eq!(root.services.len(), 2);
+        assert_eq!(root.services[0].code.as_u16(), 0x0009);
+        assert_eq!(root.services[1].code.as_u16(), 0x2009);
+
+        assert_eq!(root.areas.len(), 1);
+        let area = &root.areas[0];
+        assert_eq!((area.number(), area.end_number()), (0x40, 0x5f));
+        let codes: Vec<u16> = area.services.iter().map(|s| s.code.as_u16()).collect();
+        assert_eq!(codes, vec![0x100b, 0x1608]);
+
+        assert_eq!(area.areas.len(), 1);
+        assert_eq!(area.areas[0].services[0].code.as_u16(), 0x1408);
+
+        let all: Vec<u16> = root.service_codes().iter().map(|c| c.as_u16()).collect();
+        assert_eq!(all, vec![0x0009, 0x2009, 0x100b, 0x1608, 0x1408]);
+    }
+
+    #[test]
+    fn build_tree_without_root_area() {
+        let root = build_tree(&[SearchServiceEntry::Service(0x090f)]);
+        assert_eq!((root.code, root.end_code), (0x0000, 0xffff));
+        assert_eq!(root.services[0].code.as_u16(), 0x090f);
+        assert!(build_tree(&[]).services.is_empty());
+    }
+}
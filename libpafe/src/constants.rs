@@ -20,6 +20,36 @@ pub const FELICA_MIN_FRAME_LEN: usize = 7;
 /// Maximum payload length for `FeliCa` frames (in bytes).
 pub const FELICA_MAX_PAYLOAD_LEN: usize = 255;
 
+/// Marker in place of `len`/`LCS` announcing a PN53x extended frame:
+/// `00 00 FF FF FF LENM LENL LCS payload DCS 00`.
+pub const EXTENDED_FRAME_MARKER: [u8; 2] = [0xFF, 0xFF];
+
+/// Minimal extended frame length in bytes: preamble (3), marker (2),
+/// length (2), LCS (1), DCS (1) and postamble (1).
+pub const EXTENDED_MIN_FRAME_LEN: usize = 10;
+
+/// Maximum payload length of an extended frame (in bytes).
+pub const EXTENDED_MAX_PAYLOAD_LEN: usize = u16::MAX as usize;
+
+/// Maximum number of services in one `FeliCa` Read/Write command.
+pub const FELICA_MAX_SERVICES: usize = 16;
+
+/// Maximum number of node codes in one `FeliCa` Request Service or
+/// Request Block Information command.
+pub const FELICA_MAX_NODES: usize = 32;
+
+/// Link-layer ACK frame sent by PN53x/RCS956 and port100 chips before
+/// the response to a command: `00 00 FF 00 FF 00`.
+pub const LINK_ACK_FRAME: [u8; 6] = [0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00];
+
+/// Link-layer NACK frame asking the other side to retransmit its last
+/// frame: `00 00 FF FF 00 00`.
+pub const LINK_NACK_FRAME: [u8; 6] = [0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00];
+
+/// Application-level error frame a PN53x chip sends instead of a
+/// response when it detects a syntax error: `00 00 FF 01 FF 7F 81 00`.
+pub const LINK_ERROR_FRAME: [u8; 8] = [0x00, 0x00, 0xFF, 0x01, 0xFF, 0x7F, 0x81, 0x00];
+
 /// PN532/PN533/RCS956 host->device prefix (`D4`) and device->host prefix (`D5`).
 ///
 /// Source: NXP PN532 / PN533 documentation (publicly available).
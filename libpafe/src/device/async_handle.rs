@@ -0,0 +1,307 @@
+// libpafe-rs/libpafe/src/device/async_handle.rs
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use crate::device::handle::{
+    WAIT_BACKOFF_INITIAL, WAIT_BACKOFF_MAX, check_idm, decode_for_model, encode_for_model,
+    polled_card, polling_command,
+};
+use crate::device::models::{DeviceModel, InitIo, InitRun, InitStep};
+use crate::device::{Initialized, RetryPolicy, Uninitialized};
+use crate::protocol::{Command, Link, LinkAction, Response};
+use crate::transport::AsyncTransport;
+use crate::types::{CommandClass, DeviceType, Idm, PollingRequest, PollingRequestData, SystemCode};
+use crate::{Error, Result};
+
+/// Async counterpart of [`Device`] driving an [`AsyncTransport`], with the
+/// same `Uninitialized`/`Initialized` type-state.
+///
+/// Waits (retry backoff, polling intervals) use `tokio::time`, so the
+/// device must be used from within a tokio runtime. Cancellation is done
+/// by dropping the future.
+///
+/// [`Device`]: crate::device::Device
+pub struct AsyncDevice<State = Uninitialized> {
+    transport: Box<dyn AsyncTransport>,
+    device_type: DeviceType,
+    model: Box<dyn DeviceModel>,
+    /// Blocks-per-command limits learned from cards that rejected larger
+    /// requests, keyed by IDm and command class.
+    block_limits: HashMap<(Idm, CommandClass), usize>,
+    /// Retries applied by `execute`.
+    retry_policy: RetryPolicy,
+    _state: PhantomData<State>,
+}
+
+impl AsyncDevice<Uninitialized> {
+    /// Create a device from an existing async transport.
+    pub fn new_with_transport(transport: Box<dyn AsyncTransport>) -> Result<Self> {
+        let device_type = transport.device_type()?;
+        let model = crate::device::models::create_model_for(device_type);
+        Ok(Self {
+            transport,
+            device_type,
+            model,
+            block_limits: HashMap::new(),
+            retry_policy: RetryPolicy::default(),
+            _state: PhantomData,
+        })
+    }
+
+    /// Reset the transport and run the model's init handshake (see
+    /// [`DeviceModel::init_steps`]).
+    pub async fn initialize(self) -> Result<AsyncDevice<Initialized>> {
+        let mut this = self;
+        this.transport.reset().await?;
+        for step in this.model.init_steps() {
+            run_init_step(&mut *this.transport, &step).await?;
+        }
+
+        Ok(AsyncDevice {
+            transport: this.transport,
+            device_type: this.device_type,
+            model: this.model,
+            block_limits: this.block_limits,
+            retry_policy: this.retry_policy,
+            _state: PhantomData,
+        })
+    }
+
+    /// Replace the retry policy used by `execute` (default:
+    /// [`RetryPolicy::default`]).
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Inspect the detected device type even before initialization.
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+}
+
+impl AsyncDevice<Initialized> {
+    /// Execute a command and return the parsed Response.
+    ///
+    /// Like [`Device::execute`], a response from another card fails with
+    /// `Error::IdmMismatch` and failed exchanges are repeated according to
+    /// the device's [`RetryPolicy`].
+    ///
+    /// [`Device::execute`]: crate::device::Device::execute
+    pub async fn execute(&mut self, cmd: Command, timeout_ms: u64) -> Result<Response> {
+        let policy = self.retry_policy;
+        let mut attempt = 1;
+        loop {
+            let result = match self.execute_unchecked(cmd.clone(), timeout_ms).await {
+                Ok(resp) => check_idm(&cmd, resp),
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if policy.should_retry(&cmd, &e, attempt) => {
+                    tokio::time::sleep(policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Execute a command once, without retries and without comparing the
+    /// response IDm with the request.
+    pub async fn execute_unchecked(&mut self, cmd: Command, timeout_ms: u64) -> Result<Response> {
+        let to_send = encode_for_model(&*self.model, &cmd, timeout_ms)?;
+        self.transport.send(&to_send).await?;
+
+        let timeout_ms = timeout_ms.max(self.model.min_timeout_ms());
+        let raw = receive_reply(&mut *self.transport, timeout_ms).await?;
+        decode_for_model(&*self.model, &cmd, &raw)
+    }
+
+    /// Retry policy applied by `execute`.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Replace the retry policy applied by `execute`.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Poll for a FeliCa card (see [`Device::polling`]).
+    ///
+    /// [`Device::polling`]: crate::device::Device::polling
+    pub async fn polling(&mut self, system_code: SystemCode) -> Result<crate::card::Card> {
+        self.polling_with_request(system_code, PollingRequest::NoRequest)
+            .await
+            .map(|(card, _)| card)
+    }
+
+    /// Polling with an explicit request code (FeliCa/Type F only).
+    pub async fn polling_with_request(
+        &mut self,
+        system_code: SystemCode,
+        request: PollingRequest,
+    ) -> Result<(crate::card::Card, Option<PollingRequestData>)> {
+        let (cmd, timeout_ms) = polling_command(system_code, request);
+        let resp = self.execute(cmd, timeout_ms).await?;
+        polled_card(resp, system_code, request)
+    }
+
+    /// Poll until a FeliCa card answers for `system_code` or `deadline`
+    /// passes (`Error::Timeout`). Attempts are spaced with the same
+    /// exponential backoff as [`Device::wait_for_card`]; drop the future
+    /// to cancel the wait.
+    ///
+    /// [`Device::wait_for_card`]: crate::device::Device::wait_for_card
+    pub async fn wait_for_card(
+        &mut self,
+        system_code: SystemCode,
+        deadline: Instant,
+    ) -> Result<crate::card::Card> {
+        let mut backoff = WAIT_BACKOFF_INITIAL;
+        loop {
+            match self.polling(system_code).await {
+                Ok(card) => return Ok(card),
+                Err(e) if crate::device::watch::means_no_card(&e) => {}
+                Err(e) => return Err(e),
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+            tokio::time::sleep(backoff.min(remaining)).await;
+            backoff = (backoff * 2).min(WAIT_BACKOFF_MAX);
+        }
+    }
+
+    /// Stream of cards arriving and leaving the field, the async
+    /// counterpart of [`Device::watch`].
+    ///
+    /// Only FeliCa (Type F) cards are looked for: Type A/B detection goes
+    /// through model routines that need a blocking transport, so other
+    /// card types in `config` are ignored. A `config` without Type F makes
+    /// the stream yield `Error::UnsupportedOperation` once and end.
+    ///
+    /// [`Device::watch`]: crate::device::Device::watch
+    pub fn watch(&mut self, config: crate::device::WatchConfig) -> crate::device::WatchStream<'_> {
+        crate::device::WatchStream::new(self, config)
+    }
+
+    /// Accessor for device type
+    pub fn device_type(&self) -> DeviceType {
+        self.device_type
+    }
+
+    /// Blocks-per-command limit previously learned for a card, if any.
+    pub(crate) fn block_limit(&self, idm: &Idm, class: CommandClass) -> Option<usize> {
+        self.block_limits.get(&(*idm, class)).copied()
+    }
+
+    /// Remember the blocks-per-command limit a card accepted.
+    pub(crate) fn set_block_limit(&mut self, idm: Idm, class: CommandClass, limit: usize) {
+        self.block_limits.insert((idm, class), limit);
+    }
+}
+
+/// Perform one step of a model's init handshake; the step is interpreted
+/// by the same [`InitRun`] as the blocking `run_init_steps`.
+async fn run_init_step(transport: &mut dyn AsyncTransport, step: &InitStep) -> Result<()> {
+    let mut run = InitRun::new(step);
+    let mut outcome = None;
+    while let Some(io) = run.next(outcome.take())? {
+        outcome = Some(match io {
+            InitIo::ControlWrite => transport
+                .vendor_control_write(step.request, step.value, step.index, &step.data)
+                .await
+                .map(|_| Vec::new()),
+            InitIo::ControlRead(timeout_ms) => {
+                transport
+                    .vendor_control_read(step.request, step.value, step.index, timeout_ms)
+                    .await
+            }
+            InitIo::Receive(timeout_ms) => transport.receive(timeout_ms).await,
+            InitIo::Send => transport.send(&step.data).await.map(|_| Vec::new()),
+            InitIo::ReceiveReply(timeout_ms) => receive_reply(transport, timeout_ms).await,
+        });
+    }
+    Ok(())
+}
+
+/// Async counterpart of the sync device's link-layer reply loop.
+async fn receive_reply(transport: &mut dyn AsyncTransport, timeout_ms: u64) -> Result<Vec<u8>> {
+    let mut link = Link::new();
+    loop {
+        let raw = transport.receive(timeout_ms).await?;
+        match link.on_frame(raw)? {
+            LinkAction::Receive => {}
+            LinkAction::Nack => transport.send(&crate::constants::LINK_NACK_FRAME).await?,
+            LinkAction::Done(reply) => return Ok(reply),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, async_mock_device, polling_frame, response_mode_frame};
+    use crate::transport::mock::MockTransport;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn initialize_replays_model_init_steps() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        mock.push_response(vec![0xAA]);
+        let dev = AsyncDevice::new_with_transport(Box::new(mock)).unwrap();
+        assert!(dev.initialize().await.is_ok());
+
+        // The S310 handshake gives up when the device never answers
+        let mock = MockTransport::new(DeviceType::S310);
+        let dev = AsyncDevice::new_with_transport(Box::new(mock)).unwrap();
+        assert!(matches!(dev.initialize().await, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn polling_returns_card() {
+        let mut dev = async_mock_device(vec![polling_frame(TEST_IDM)]).await;
+        let card = dev.polling(SystemCode::new(0xffff)).await.unwrap();
+        assert_eq!(card.idm(), Some(&Idm::from_bytes(TEST_IDM)));
+    }
+
+    #[tokio::test]
+    async fn execute_retries_then_checks_idm() {
+        let mut dev = async_mock_device(vec![vec![0xde, 0xad], polling_frame(TEST_IDM)]).await;
+        dev.set_retry_policy(RetryPolicy::new().backoff(Duration::ZERO));
+        assert!(dev.polling(SystemCode::new(0xffff)).await.is_ok());
+
+        // RequestResponse answered by the polled card but addressed elsewhere
+        let mut dev = async_mock_device(vec![response_mode_frame(TEST_IDM)]).await;
+        let cmd = Command::RequestResponse {
+            idm: Idm::from_bytes([9; 8]),
+        };
+        assert!(matches!(
+            dev.execute(cmd, 100).await,
+            Err(Error::IdmMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn wait_for_card_stops_at_deadline() {
+        let mut dev = async_mock_device(vec![]).await;
+        let deadline = Instant::now() + Duration::from_millis(30);
+        assert!(matches!(
+            dev.wait_for_card(SystemCode::new(0xffff), deadline).await,
+            Err(Error::Timeout)
+        ));
+
+        let mut dev = async_mock_device(vec![vec![0xde, 0xad], polling_frame(TEST_IDM)]).await;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        assert!(
+            dev.wait_for_card(SystemCode::new(0xffff), deadline)
+                .await
+                .is_ok()
+        );
+    }
+}
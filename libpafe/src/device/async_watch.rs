@@ -0,0 +1,204 @@
+// libpafe-rs/libpafe/src/device/async_watch.rs
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::card::Card;
+use crate::device::watch::{Present, departure, means_no_card};
+use crate::device::{AsyncDevice, CardEvent, Initialized, WatchConfig};
+use crate::types::CardType;
+use crate::{Error, Result};
+
+/// Watch loop state; moved into the pending future while a round runs.
+struct WatchState<'d> {
+    device: &'d mut AsyncDevice<Initialized>,
+    config: WatchConfig,
+    present: Option<Present>,
+    started: bool,
+}
+
+type NextEvent<'d> = Pin<Box<dyn Future<Output = (WatchState<'d>, Result<CardEvent>)> + Send + 'd>>;
+
+/// Stream returned by [`AsyncDevice::watch`]. Like [`Watch`] it never ends
+/// on its own; errors that do not simply mean "no card" are yielded and
+/// the caller decides whether to keep watching. The one exception is a
+/// config without [`CardType::TypeF`]: the stream yields
+/// `Error::UnsupportedOperation` once and then ends.
+///
+/// [`Watch`]: crate::device::Watch
+pub struct WatchStream<'d> {
+    state: Option<WatchState<'d>>,
+    pending: Option<NextEvent<'d>>,
+}
+
+impl<'d> WatchStream<'d> {
+    pub(crate) fn new(device: &'d mut AsyncDevice<Initialized>, config: WatchConfig) -> Self {
+        Self {
+            state: Some(WatchState {
+                device,
+                config,
+                present: None,
+                started: false,
+            }),
+            pending: None,
+        }
+    }
+
+    /// Card currently considered present. Only known between two events;
+    /// `None` while the stream is waiting for the next one.
+    pub fn current(&self) -> Option<&Card> {
+        self.state
+            .as_ref()
+            .and_then(|s| s.present.as_ref())
+            .map(|p| &p.card)
+    }
+}
+
+impl WatchState<'_> {
+    async fn next_event(&mut self) -> Result<CardEvent> {
+        loop {
+            if self.started {
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+            self.started = true;
+            if let Some(event) = self.step().await? {
+                return Ok(event);
+            }
+        }
+    }
+
+    async fn step(&mut self) -> Result<Option<CardEvent>> {
+        match self.present.take() {
+            Some(mut present) => {
+                if self.still_present(&present.card).await? {
+                    present.misses = 0;
+                } else {
+                    present.misses += 1;
+                    if present.misses >= self.config.debounce {
+                        return Ok(Some(departure(&present.card)));
+                    }
+                }
+                self.present = Some(present);
+                Ok(None)
+            }
+            None => {
+                let found = self.detect().await?;
+                Ok(found.map(|card| {
+                    self.present = Some(Present {
+                        card: card.clone(),
+                        misses: 0,
+                    });
+                    CardEvent::Arrived(card)
+                }))
+            }
+        }
+    }
+
+    /// Poll every configured system code once.
+    async fn detect(&mut self) -> Result<Option<Card>> {
+        for &sc in &self.config.system_codes {
+            match self.device.polling(sc).await {
+                Ok(card) => return Ok(Some(card)),
+                Err(e) if means_no_card(&e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether `card` still answers RequestResponse.
+    async fn still_present(&mut self, card: &Card) -> Result<bool> {
+        match crate::card::operations::async_ops::request_response_mode(card, self.device).await {
+            Ok(_) => Ok(true),
+            Err(e) if means_no_card(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Stream for WatchStream<'_> {
+    type Item = Result<CardEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.pending.is_none() {
+            let Some(mut state) = this.state.take() else {
+                return Poll::Ready(None);
+            };
+            if !state.config.card_types.contains(&CardType::TypeF) {
+                // Nothing could ever be detected: report it and end
+                return Poll::Ready(Some(Err(Error::UnsupportedOperation(
+                    "async watch only detects FeliCa (Type F) cards".into(),
+                ))));
+            }
+            this.pending = Some(Box::pin(async move {
+                let event = state.next_event().await;
+                (state, event)
+            }));
+        }
+
+        let pending = this.pending.as_mut().expect("pending round was just set");
+        match pending.as_mut().poll(cx) {
+            Poll::Ready((state, event)) => {
+                this.pending = None;
+                this.state = Some(state);
+                Poll::Ready(Some(event))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, async_mock_device, polling_frame, response_mode_frame};
+    use crate::types::Idm;
+    use std::time::Duration;
+
+    async fn next(stream: &mut WatchStream<'_>) -> Option<Result<CardEvent>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn stream_reports_arrival_then_departure() {
+        let mut dev = async_mock_device(vec![
+            vec![0xde, 0xad],              // no card yet
+            polling_frame(TEST_IDM),       // arrival
+            response_mode_frame(TEST_IDM), // still present
+            vec![0xde, 0xad],              // first miss
+            vec![0xde, 0xad],              // second miss: departure
+        ])
+        .await;
+        let mut stream = dev.watch(WatchConfig::new().poll_interval(Duration::ZERO));
+
+        match next(&mut stream).await {
+            Some(Ok(CardEvent::Arrived(card))) => {
+                assert_eq!(card.idm(), Some(&Idm::from_bytes(TEST_IDM)))
+            }
+            other => panic!("expected arrival, got {:?}", other),
+        }
+        assert!(stream.current().is_some());
+        match next(&mut stream).await {
+            Some(Ok(CardEvent::Departed(idm))) => assert_eq!(idm, Idm::from_bytes(TEST_IDM)),
+            other => panic!("expected departure, got {:?}", other),
+        }
+        assert!(stream.current().is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_without_type_f_reports_unsupported_once() {
+        let mut dev = async_mock_device(vec![]).await;
+        let config = WatchConfig::new().card_types(vec![CardType::TypeA, CardType::TypeB]);
+        let mut stream = dev.watch(config);
+
+        assert!(matches!(
+            next(&mut stream).await,
+            Some(Err(Error::UnsupportedOperation(_)))
+        ));
+        assert!(next(&mut stream).await.is_none());
+    }
+}
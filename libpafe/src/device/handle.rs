@@ -1,13 +1,27 @@
 // libpafe-rs/libpafe/src/device/handle.rs
 
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
+use crate::device::RetryPolicy;
 use crate::protocol::codec;
 use crate::protocol::{Command, Response};
+use crate::protocol::{Link, LinkAction};
 use crate::transport::Transport;
-use crate::types::{DeviceType, SystemCode};
+use crate::types::{
+    CommandClass, DeviceType, Idm, PollingRequest, PollingRequestData, SystemCode, TimeSlots,
+};
+use crate::utils::CancelToken;
 use crate::{Error, Result};
 
+/// First pause between two `wait_for_card` polling attempts.
+pub(crate) const WAIT_BACKOFF_INITIAL: Duration = Duration::from_millis(10);
+/// Longest pause between two `wait_for_card` polling attempts.
+pub(crate) const WAIT_BACKOFF_MAX: Duration = Duration::from_millis(250);
+/// Chip-side passive activation retries used while `wait_for_card` runs.
+const WAIT_PASSIVE_ACTIVATION_RETRIES: u8 = 0x10;
+
 /// Type-state markers
 pub struct Uninitialized;
 pub struct Initialized;
@@ -17,6 +31,11 @@ pub struct Device<State = Uninitialized> {
     transport: Box<dyn Transport>,
     device_type: DeviceType,
     model: Box<dyn crate::device::models::DeviceModel>,
+    /// Blocks-per-command limits learned from cards that rejected larger
+    /// requests, keyed by IDm and command class.
+    block_limits: HashMap<(Idm, CommandClass), usize>,
+    /// Retries applied by `execute`.
+    retry_policy: RetryPolicy,
     _state: PhantomData<State>,
 }
 
@@ -30,6 +49,8 @@ impl Device<Uninitialized> {
             transport,
             device_type,
             model,
+            block_limits: HashMap::new(),
+            retry_policy: RetryPolicy::default(),
             _state: PhantomData,
         })
     }
@@ -49,10 +70,19 @@ impl Device<Uninitialized> {
             transport: this.transport,
             device_type: this.device_type,
             model: this.model,
+            block_limits: this.block_limits,
+            retry_policy: this.retry_policy,
             _state: PhantomData,
         })
     }
 
+    /// Replace the retry policy used by `execute` (default:
+    /// [`RetryPolicy::default`]).
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
     /// Inspect the detected device type even before initialization.
     pub fn device_type(&self) -> DeviceType {
         self.device_type
@@ -61,85 +91,162 @@ impl Device<Uninitialized> {
 
 impl Device<Initialized> {
     /// Execute a command and return the parsed Response.
+    ///
+    /// The response must come from the card the command addressed: a
+    /// response carrying another IDm (a second card in the field, stale
+    /// buffered data) fails with `Error::IdmMismatch`. Use
+    /// [`Device::execute_unchecked`] to see such responses anyway.
+    ///
+    /// Failed exchanges are repeated according to the device's
+    /// [`RetryPolicy`]; every card operation goes through here.
     pub fn execute(&mut self, cmd: Command, timeout_ms: u64) -> Result<Response> {
-        // Prepare both the raw command payload and the fully-framed
-        // FeliCa frame. Device models may choose which form they want to
-        // send using the wrap_command hook.
-        let payload = cmd.encode();
-        let framed = codec::encode_command_frame(&cmd)?;
-
-        // Let the device model wrap the outgoing bytes (RCS956 envelopes
-        // for S330, vendor-control envelopes for others, etc.).
-        // Use the cached model instance to wrap/unwrap model-specific
-        // transport payloads.
-        let to_send = self.model.wrap_command(&framed, &payload);
+        let policy = self.retry_policy;
+        policy.run(&cmd, || {
+            let resp = self.execute_unchecked(cmd.clone(), timeout_ms)?;
+            check_idm(&cmd, resp)
+        })
+    }
 
+    /// Retry policy applied by `execute`.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Replace the retry policy applied by `execute`.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Execute a command once, without retries and without comparing the
+    /// response IDm with the request, e.g. for raw diagnostics.
+    ///
+    /// `timeout_ms` is raised to the model's floor when the reader needs
+    /// longer than the card (see `DeviceModel::min_timeout_ms`).
+    pub fn execute_unchecked(&mut self, cmd: Command, timeout_ms: u64) -> Result<Response> {
+        let to_send = encode_for_model(&*self.model, &cmd, timeout_ms)?;
         self.transport.send(&to_send)?;
 
-        let raw_resp = self.transport.receive(timeout_ms)?;
-
-        // Allow an S330/RCS956-style exchange to complete: many PN53x
-        // devices return an immediate ACK (00 00 FF 00 FF 00) followed
-        // by the actual response payload. If we detect an ACK-only read
-        // and this is an S330 device, perform a second receive and
-        // append the bytes so model-specific unwrapping can locate the
-        // inner FeliCa frame.
-        let mut raw = raw_resp;
-        let pn532_ack = [0x00u8, 0x00u8, 0xFFu8, 0x00u8, 0xFFu8, 0x00u8];
-        if self.device_type == crate::types::DeviceType::S330
-            && raw.starts_with(&pn532_ack)
-            && raw.len() == pn532_ack.len()
-        {
-            // ACK-only read: attempt to read the real response (best-effort)
-            if let Ok(mut follow) = self.transport.receive(timeout_ms) {
-                raw.append(&mut follow);
+        let timeout_ms = timeout_ms.max(self.model.min_timeout_ms());
+        let raw = receive_reply(&mut *self.transport, timeout_ms)?;
+        decode_for_model(&*self.model, &cmd, &raw)
+    }
+
+    /// Block until a FeliCa card answers Polling for `system_code`, the
+    /// `deadline` passes (`Error::Timeout`) or `cancel` fires
+    /// (`Error::Cancelled`).
+    ///
+    /// Attempts are spaced with an exponential backoff. On S330 the
+    /// RCS956 is told to retry passive activation itself
+    /// (`MxRtyPassiveActivation`) for the duration of the wait, so most
+    /// of the waiting happens on the chip; the setting is restored
+    /// afterwards.
+    pub fn wait_for_card(
+        &mut self,
+        system_code: SystemCode,
+        deadline: Instant,
+        cancel: &CancelToken,
+    ) -> Result<crate::card::Card> {
+        self.model.set_passive_activation_retries(
+            &mut *self.transport,
+            Some(WAIT_PASSIVE_ACTIVATION_RETRIES),
+        )?;
+        let result = self.wait_for_card_inner(system_code, deadline, cancel);
+        let restored = self
+            .model
+            .set_passive_activation_retries(&mut *self.transport, None);
+        let card = result?;
+        restored?;
+        Ok(card)
+    }
+
+    fn wait_for_card_inner(
+        &mut self,
+        system_code: SystemCode,
+        deadline: Instant,
+        cancel: &CancelToken,
+    ) -> Result<crate::card::Card> {
+        let mut backoff = WAIT_BACKOFF_INITIAL;
+        loop {
+            if cancel.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+            match self.polling(system_code) {
+                Ok(card) => return Ok(card),
+                Err(e) if crate::device::watch::means_no_card(&e) => {}
+                Err(e) => return Err(e),
             }
-        }
 
-        // Allow the model to extract the inner FeliCa frame or payload
-        // from a device-specific response format.
-        let inner = self.model.unwrap_response(cmd.command_code(), &raw)?;
-
-        // Try the normal decode path first. If decoding fails on
-        // S330/PN533 devices, attempt a more permissive extraction of
-        // candidate FeliCa frames (handles ACKs, concatenated reads,
-        // and other vendor-specific wrappers) and decode each until
-        // one succeeds.
-        match codec::decode_response_frame(cmd.command_code(), &inner) {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                if self.device_type == crate::types::DeviceType::S330 {
-                    let candidates = self
-                        .model
-                        .extract_candidate_frames(&raw, cmd.command_code());
-                    for frame in candidates {
-                        if let Ok(r2) = codec::decode_response_frame(cmd.command_code(), &frame) {
-                            return Ok(r2);
-                        }
-                    }
-                }
-                Err(e)
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+            if cancel.sleep(backoff.min(remaining)) {
+                return Err(Error::Cancelled);
             }
+            backoff = (backoff * 2).min(WAIT_BACKOFF_MAX);
         }
     }
 
+    /// Watch the field for cards arriving and leaving. See [`WatchConfig`]
+    /// for polling interval, debounce, system codes and card types.
+    ///
+    /// [`WatchConfig`]: crate::device::WatchConfig
+    pub fn watch(&mut self, config: crate::device::WatchConfig) -> crate::device::Watch<'_> {
+        crate::device::Watch::new(self, config)
+    }
+
+    /// Poll for a FeliCa card and open a [`CardSession`] bound to it.
+    ///
+    /// [`CardSession`]: crate::card::CardSession
+    pub fn polling_session(
+        &mut self,
+        system_code: SystemCode,
+    ) -> Result<crate::card::CardSession<'_>> {
+        let card = self.polling(system_code)?;
+        crate::card::CardSession::new(self, card)
+    }
+
     /// High-level polling convenience method (FeliCa/Type F only).
+    ///
+    /// Sends request code 0, so the returned card carries the polled
+    /// `system_code`. Use [`Device::polling_with_request`] with
+    /// [`PollingRequest::SystemCode`] to learn which system responded to a
+    /// wildcard.
     pub fn polling(&mut self, system_code: SystemCode) -> Result<crate::card::Card> {
-        let cmd = Command::Polling {
+        self.polling_with_request(system_code, PollingRequest::NoRequest)
+            .map(|(card, _)| card)
+    }
+
+    /// Polling with an explicit request code (FeliCa/Type F only).
+    ///
+    /// Returns the card together with the decoded Request Data, or `None`
+    /// when the request carried no request code or the card sent none.
+    pub fn polling_with_request(
+        &mut self,
+        system_code: SystemCode,
+        request: PollingRequest,
+    ) -> Result<(crate::card::Card, Option<PollingRequestData>)> {
+        let (cmd, timeout_ms) = polling_command(system_code, request);
+        let resp = self.execute(cmd, timeout_ms)?;
+        polled_card(resp, system_code, request)
+    }
+
+    /// Detect every FeliCa card in the field by opening `slots` Polling
+    /// time slots. Cards are de-duplicated by IDm.
+    ///
+    /// S310/S320 perform FeliCa time-slot polling; S330 delegates to its
+    /// InListPassiveTarget routine with `slots.count()` as target limit.
+    pub fn polling_time_slots(
+        &mut self,
+        system_code: SystemCode,
+        slots: TimeSlots,
+    ) -> Result<Vec<crate::card::Card>> {
+        self.list_passive_targets(
+            crate::types::CardType::TypeF,
             system_code,
-            request_code: 0,
-            time_slot: 0,
-        };
-        let resp = self.execute(cmd, 1000)?;
-
-        match resp {
-            Response::Polling {
-                idm,
-                pmm,
-                system_code,
-            } => Ok(crate::card::Card::new(idm, pmm, system_code)),
-            _ => Err(Error::PollingFailed),
-        }
+            slots.count(),
+            crate::utils::response_timeout_ms(slots.max_response_time()),
+        )
     }
 
     /// Model-specific multi-target polling (if supported by the device
@@ -168,18 +275,353 @@ impl Device<Initialized> {
     pub fn device_type(&self) -> DeviceType {
         self.device_type
     }
+
+    /// Blocks-per-command limit previously learned for a card, if any.
+    pub(crate) fn block_limit(&self, idm: &Idm, class: CommandClass) -> Option<usize> {
+        self.block_limits.get(&(*idm, class)).copied()
+    }
+
+    /// Remember the blocks-per-command limit a card accepted.
+    pub(crate) fn set_block_limit(&mut self, idm: Idm, class: CommandClass, limit: usize) {
+        self.block_limits.insert((idm, class), limit);
+    }
+}
+
+/// Bytes to send for `cmd` on a device driven by `model`.
+pub(crate) fn encode_for_model(
+    model: &dyn crate::device::models::DeviceModel,
+    cmd: &Command,
+    timeout_ms: u64,
+) -> Result<Vec<u8>> {
+    // Prepare both the raw command payload and the fully-framed
+    // FeliCa frame. Device models may choose which form they want to
+    // send using the wrap_command hook.
+    let payload = cmd.encode()?;
+    let framed = codec::encode_command_frame(cmd)?;
+
+    // Let the device model wrap the outgoing bytes (RCS956 envelopes
+    // for S330, vendor-control envelopes for others, etc.).
+    Ok(model.wrap_command(&framed, &payload, timeout_ms))
+}
+
+/// Read the reply to the command just sent, running the link-layer
+/// exchange (waiting out the ACK, NACKing corrupted frames).
+pub(crate) fn receive_reply(transport: &mut dyn Transport, timeout_ms: u64) -> Result<Vec<u8>> {
+    let mut link = Link::new();
+    loop {
+        let raw = transport.receive(timeout_ms)?;
+        match link.on_frame(raw)? {
+            LinkAction::Receive => {}
+            LinkAction::Nack => transport.send(&crate::constants::LINK_NACK_FRAME)?,
+            LinkAction::Done(reply) => return Ok(reply),
+        }
+    }
+}
+
+/// Decode the raw bytes a device returned for `cmd`.
+pub(crate) fn decode_for_model(
+    model: &dyn crate::device::models::DeviceModel,
+    cmd: &Command,
+    raw: &[u8],
+) -> Result<Response> {
+    // Allow the model to extract the inner FeliCa frame or payload
+    // from a device-specific response format.
+    let inner = model.unwrap_response(cmd.command_code(), raw)?;
+
+    // Try the normal decode path first. If decoding fails, let the
+    // model offer candidate FeliCa frames (S330/PN533: ACKs,
+    // concatenated reads and other vendor-specific wrappers; none for
+    // other models) and decode each until one succeeds.
+    codec::decode_response_frame(cmd.command_code(), &inner).or_else(|e| {
+        model
+            .extract_candidate_frames(raw, cmd.command_code())
+            .iter()
+            .find_map(|frame| codec::decode_response_frame(cmd.command_code(), frame).ok())
+            .ok_or(e)
+    })
+}
+
+/// Reject a response that does not come from the card `cmd` addressed.
+pub(crate) fn check_idm(cmd: &Command, resp: Response) -> Result<Response> {
+    if let Some(expected) = cmd.idm()
+        && *resp.idm() != expected
+    {
+        return Err(Error::IdmMismatch {
+            expected,
+            actual: *resp.idm(),
+        });
+    }
+    Ok(resp)
+}
+
+/// Interpret a Polling response for a request sent with `system_code`.
+pub(crate) fn polled_card(
+    resp: Response,
+    system_code: SystemCode,
+    request: PollingRequest,
+) -> Result<(crate::card::Card, Option<PollingRequestData>)> {
+    match resp {
+        Response::Polling {
+            idm,
+            pmm,
+            request_data,
+        } => Ok(crate::card::Card::from_polling(
+            idm,
+            pmm,
+            system_code,
+            request,
+            request_data,
+        )),
+        _ => Err(Error::PollingFailed),
+    }
+}
+
+/// Single-slot Polling command and its receive timeout.
+pub(crate) fn polling_command(system_code: SystemCode, request: PollingRequest) -> (Command, u64) {
+    let cmd = Command::Polling {
+        system_code,
+        request_code: request.request_code(),
+        time_slot: TimeSlots::One.time_slot_byte(),
+    };
+    let timeout_ms = crate::utils::response_timeout_ms(TimeSlots::One.max_response_time());
+    (cmd, timeout_ms)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::protocol::Command;
+    use crate::test_support::{
+        SharedMock, TEST_IDM, mock_device, polling_frame, response_mode_frame, shared_mock_device,
+    };
     use crate::transport::mock::MockTransport;
     use crate::types::DeviceType;
     use crate::types::SystemCode;
     use std::cell::RefCell;
     use std::rc::Rc;
 
+    // Transport recording the receive timeouts it was asked for
+    struct TimeoutRecorder {
+        inner: MockTransport,
+        timeouts: Rc<RefCell<Vec<u64>>>,
+    }
+    impl crate::transport::traits::Transport for TimeoutRecorder {
+        fn send(&mut self, data: &[u8]) -> Result<()> {
+            self.inner.send(data)
+        }
+        fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
+            self.timeouts.borrow_mut().push(timeout_ms);
+            self.inner.receive(timeout_ms)
+        }
+        fn device_type(&self) -> Result<DeviceType> {
+            self.inner.device_type()
+        }
+        fn reset(&mut self) -> Result<()> {
+            self.inner.reset()
+        }
+    }
+
+    #[test]
+    fn polling_timeout_respects_model_floor() {
+        for (device_type, floor) in [(DeviceType::S320, 0), (DeviceType::S330, 1000)] {
+            let timeouts = Rc::new(RefCell::new(Vec::new()));
+            let mut inner = MockTransport::new(device_type);
+            inner.push_response(vec![0xAA]);
+            let transport = TimeoutRecorder {
+                inner,
+                timeouts: timeouts.clone(),
+            };
+            let mut dev = Device::new_with_transport(Box::new(transport))
+                .unwrap()
+                .with_retry_policy(RetryPolicy::none())
+                .initialize()
+                .unwrap();
+            timeouts.borrow_mut().clear();
+
+            let (_, polling_ms) =
+                polling_command(SystemCode::new(0x0003), PollingRequest::NoRequest);
+            let _ = dev.polling(SystemCode::new(0x0003));
+            assert_eq!(*timeouts.borrow(), vec![polling_ms.max(floor)]);
+        }
+    }
+
+    #[test]
+    fn execute_rejects_response_from_other_card() {
+        let mut dev = mock_device(vec![response_mode_frame([9; 8])]);
+        let cmd = Command::RequestResponse {
+            idm: Idm::from_bytes([1; 8]),
+        };
+        match dev.execute(cmd, 100) {
+            Err(Error::IdmMismatch { expected, actual }) => {
+                assert_eq!(expected, Idm::from_bytes([1; 8]));
+                assert_eq!(actual, Idm::from_bytes([9; 8]));
+            }
+            other => panic!("expected IdmMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn execute_retries_garbled_response() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        crate::test_support::seed_init_and_frames(
+            &mut mock,
+            vec![vec![0xde, 0xad], response_mode_frame([1; 8])],
+        );
+        let mut dev = Device::new_with_transport(Box::new(mock))
+            .unwrap()
+            .initialize()
+            .unwrap();
+        let cmd = Command::RequestResponse {
+            idm: Idm::from_bytes([1; 8]),
+        };
+        assert!(dev.execute(cmd.clone(), 100).is_ok());
+
+        // Without retries the garbled frame surfaces
+        dev.set_retry_policy(RetryPolicy::none());
+        assert!(dev.execute(cmd, 100).unwrap_err().is_transient());
+    }
+
+    #[test]
+    fn execute_sends_writes_once() {
+        // The write may have reached the card even though its response
+        // was lost, so the default policy must not send it again
+        let (mut dev, mock) = crate::test_support::shared_mock_device(vec![]);
+        dev.set_retry_policy(RetryPolicy::new().backoff(Duration::ZERO));
+        let cmd = Command::WriteWithoutEncryption {
+            idm: Idm::from_bytes(TEST_IDM),
+            service: crate::types::ServiceCode::new(0x0009),
+            block: crate::types::BlockElement::new(0, crate::types::AccessMode::Normal, 0),
+            data: crate::types::BlockData::from_bytes([0; 16]),
+        };
+        assert!(matches!(dev.execute(cmd, 100), Err(Error::Timeout)));
+        assert_eq!(mock.borrow().sent.len(), 1);
+    }
+
+    #[test]
+    fn execute_unchecked_returns_foreign_response() {
+        let mut dev = mock_device(vec![response_mode_frame([9; 8])]);
+        let cmd = Command::RequestResponse {
+            idm: Idm::from_bytes([1; 8]),
+        };
+        let resp = dev.execute_unchecked(cmd, 100).unwrap();
+        assert_eq!(*resp.idm(), Idm::from_bytes([9; 8]));
+    }
+
+    #[test]
+    fn execute_nacks_corrupted_frame_after_ack() {
+        let good = polling_frame(TEST_IDM);
+        let mut bad = good.clone();
+        let dcs_idx = bad.len() - 2;
+        bad[dcs_idx] ^= 0xFF;
+
+        let inner = Rc::new(RefCell::new(MockTransport::new(DeviceType::S320)));
+        crate::test_support::seed_init_and_frames(
+            &mut inner.borrow_mut(),
+            vec![crate::constants::LINK_ACK_FRAME.to_vec(), bad, good],
+        );
+        let boxed: Box<dyn Transport> = Box::new(SharedMock(inner.clone()));
+        let mut dev = Device::new_with_transport(boxed)
+            .unwrap()
+            .with_retry_policy(RetryPolicy::none())
+            .initialize()
+            .unwrap();
+
+        let card = dev.polling(SystemCode::new(0x0003)).unwrap();
+        assert_eq!(card.idm(), Some(&Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8])));
+        // The command, then a single NACK for the corrupted frame
+        assert_eq!(
+            inner.borrow().sent.last().unwrap(),
+            &crate::constants::LINK_NACK_FRAME
+        );
+    }
+
+    #[test]
+    fn execute_maps_error_frame() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        crate::test_support::seed_init_and_frames(
+            &mut mock,
+            vec![
+                crate::constants::LINK_ACK_FRAME.to_vec(),
+                crate::constants::LINK_ERROR_FRAME.to_vec(),
+            ],
+        );
+        let mut dev = Device::new_with_transport(Box::new(mock))
+            .unwrap()
+            .initialize()
+            .unwrap();
+        let cmd = Command::RequestResponse {
+            idm: Idm::from_bytes([1; 8]),
+        };
+        assert!(matches!(dev.execute(cmd, 100), Err(Error::ErrorFrame)));
+    }
+
+    #[test]
+    fn wait_for_card_retries_until_card_answers() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        crate::test_support::seed_init_and_frames(
+            &mut mock,
+            vec![vec![0xde, 0xad], vec![0xde, 0xad], polling_frame(TEST_IDM)],
+        );
+        let mut dev = Device::new_with_transport(Box::new(mock))
+            .unwrap()
+            .with_retry_policy(RetryPolicy::none())
+            .initialize()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let card = dev
+            .wait_for_card(SystemCode::new(0x0003), deadline, &CancelToken::new())
+            .unwrap();
+        assert_eq!(card.idm(), Some(&Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8])));
+    }
+
+    #[test]
+    fn wait_for_card_stops_at_deadline_or_cancel() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        crate::test_support::seed_init_and_frames(&mut mock, vec![]);
+        let mut dev = Device::new_with_transport(Box::new(mock))
+            .unwrap()
+            .initialize()
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(30);
+        let r = dev.wait_for_card(SystemCode::new(0x0003), deadline, &CancelToken::new());
+        assert!(matches!(r, Err(Error::Timeout)));
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let r = dev.wait_for_card(SystemCode::new(0x0003), deadline, &cancel);
+        assert!(matches!(r, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn wait_for_card_configures_s330_retries() {
+        let mock = Rc::new(RefCell::new(MockTransport::new(DeviceType::S330)));
+        let shared = SharedMock(mock.clone());
+        let mut dev = Device::new_with_transport(Box::new(shared))
+            .unwrap()
+            .initialize()
+            .unwrap();
+        mock.borrow_mut().sent.clear();
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let r = dev.wait_for_card(SystemCode::new(0x0003), deadline, &cancel);
+        assert!(matches!(r, Err(Error::Cancelled)));
+
+        let sent = &mock.borrow().sent;
+        assert_eq!(
+            sent.first().unwrap(),
+            &vec![0xD4, 0x32, 0x05, 0xFF, 0x01, 0x10]
+        );
+        assert_eq!(
+            sent.last().unwrap(),
+            &vec![0xD4, 0x32, 0x05, 0xFF, 0x01, 0xFF]
+        );
+    }
+
     #[test]
     fn mock_device_polling() {
         // Prepare a mock transport with a pre-seeded polling response frame
@@ -209,6 +651,112 @@ mod tests {
         assert_eq!(card.system_code().unwrap().as_u16(), 0x0a0b);
     }
 
+    #[test]
+    fn mock_device_polling_sends_no_request_code() {
+        let (mut dev, mock) = shared_mock_device(vec![polling_frame(TEST_IDM)]);
+
+        let card = dev.polling(SystemCode::new(0x0a0b)).unwrap();
+        assert_eq!(card.system_code(), Some(SystemCode::new(0x0a0b)));
+
+        let expected = crate::protocol::codec::encode_command_frame(&Command::Polling {
+            system_code: SystemCode::new(0x0a0b),
+            request_code: 0x00,
+            time_slot: 0,
+        })
+        .unwrap();
+        assert_eq!(mock.borrow().sent, vec![expected]);
+    }
+
+    #[test]
+    fn mock_device_polling_with_communication_performance() {
+        let inner = Rc::new(RefCell::new(MockTransport::new(DeviceType::S320)));
+
+        let mut payload = vec![0x01];
+        payload.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]); // idm
+        payload.extend_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]); // pmm
+        payload.extend_from_slice(&[0x00, 0x03]); // 212/424 kbps
+        inner.borrow_mut().push_response(vec![0xAA]);
+        inner
+            .borrow_mut()
+            .push_response(crate::protocol::Frame::encode(&payload).unwrap());
+
+        let boxed: Box<dyn Transport> = Box::new(SharedMock(inner.clone()));
+        let mut dev = Device::new_with_transport(boxed)
+            .unwrap()
+            .initialize()
+            .unwrap();
+
+        let (card, data) = dev
+            .polling_with_request(
+                SystemCode::new(0x0003),
+                PollingRequest::CommunicationPerformance,
+            )
+            .unwrap();
+        assert_eq!(card.system_code().unwrap().as_u16(), 0x0003);
+        match data {
+            Some(PollingRequestData::CommunicationPerformance(perf)) => {
+                assert!(perf.supports_212kbps());
+                assert!(perf.supports_424kbps());
+                assert!(!perf.supports_848kbps());
+            }
+            other => panic!("expected communication performance, got {:?}", other),
+        }
+
+        let expected = crate::protocol::codec::encode_command_frame(&Command::Polling {
+            system_code: SystemCode::new(0x0003),
+            request_code: 0x02,
+            time_slot: 0,
+        })
+        .unwrap();
+        assert_eq!(inner.borrow().sent.last().unwrap(), &expected);
+    }
+
+    #[test]
+    fn mock_device_polling_time_slots_collects_cards() {
+        let inner = Rc::new(RefCell::new(MockTransport::new(DeviceType::S320)));
+        let polling_frame = |idm: [u8; 8]| {
+            let mut payload = vec![0x01];
+            payload.extend_from_slice(&idm);
+            payload.extend_from_slice(&[0; 8]); // pmm
+            payload.extend_from_slice(&SystemCode::new(0x0003).to_le_bytes());
+            crate::protocol::Frame::encode(&payload).unwrap()
+        };
+
+        inner.borrow_mut().push_response(vec![0xAA]);
+        // Two cards in one read, then a repeat of the first in a later slot
+        let mut both = polling_frame([1; 8]);
+        both.extend_from_slice(&polling_frame([2; 8]));
+        inner.borrow_mut().push_response(both);
+        inner.borrow_mut().push_response(polling_frame([1; 8]));
+
+        let boxed: Box<dyn Transport> = Box::new(SharedMock(inner.clone()));
+        let mut dev = Device::new_with_transport(boxed)
+            .unwrap()
+            .initialize()
+            .unwrap();
+
+        let cards = dev
+            .polling_time_slots(SystemCode::new(0xffff), TimeSlots::Four)
+            .unwrap();
+        let idms: Vec<_> = cards.iter().map(|c| *c.idm().unwrap()).collect();
+        assert_eq!(
+            idms,
+            vec![
+                crate::types::Idm::from_bytes([1; 8]),
+                crate::types::Idm::from_bytes([2; 8])
+            ]
+        );
+        assert_eq!(cards[0].system_code().unwrap().as_u16(), 0x0003);
+
+        let expected = crate::protocol::codec::encode_command_frame(&Command::Polling {
+            system_code: SystemCode::new(0xffff),
+            request_code: 0x01,
+            time_slot: 0x03,
+        })
+        .unwrap();
+        assert_eq!(inner.borrow().sent.last().unwrap(), &expected);
+    }
+
     #[test]
     fn device_execute_sends_framed_command() {
         // Shared MockTransport so test can inspect sent messages after Device owns it
@@ -226,32 +774,7 @@ mod tests {
         inner.borrow_mut().push_response(vec![0xAA]);
         inner.borrow_mut().push_response(frame);
 
-        // Transport wrapper that delegates into Rc<RefCell<MockTransport>>
-        struct SharedTransport {
-            inner: Rc<RefCell<MockTransport>>,
-        }
-        impl SharedTransport {
-            fn new(inner: Rc<RefCell<MockTransport>>) -> Self {
-                Self { inner }
-            }
-        }
-        impl crate::transport::traits::Transport for SharedTransport {
-            fn send(&mut self, data: &[u8]) -> Result<()> {
-                self.inner.borrow_mut().send(data)
-            }
-            fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
-                self.inner.borrow_mut().receive(timeout_ms)
-            }
-            fn device_type(&self) -> Result<DeviceType> {
-                self.inner.borrow().device_type()
-            }
-            fn reset(&mut self) -> Result<()> {
-                self.inner.borrow_mut().reset()
-            }
-        }
-
-        let boxed: Box<dyn crate::transport::Transport> =
-            Box::new(SharedTransport::new(inner.clone()));
+        let boxed: Box<dyn crate::transport::Transport> = Box::new(SharedMock(inner.clone()));
         let device = Device::new_with_transport(boxed).unwrap();
         let mut dev = device.initialize().unwrap();
 
@@ -1,6 +1,20 @@
 // libpafe-rs/libpafe/src/device/mod.rs
 
+#[cfg(feature = "async")]
+pub mod async_handle;
+#[cfg(feature = "async")]
+pub mod async_watch;
 pub mod handle;
 pub mod models;
+pub mod reader;
+pub mod retry;
+pub mod watch;
 
+#[cfg(feature = "async")]
+pub use async_handle::AsyncDevice;
+#[cfg(feature = "async")]
+pub use async_watch::WatchStream;
 pub use handle::{Device, Initialized, Uninitialized};
+pub use reader::ReaderHandle;
+pub use retry::{RetryPolicy, retry_idempotent};
+pub use watch::{CardEvent, Watch, WatchConfig};
@@ -3,20 +3,79 @@
 use crate::Result;
 use crate::types::DeviceType;
 
-pub trait DeviceModel {
+/// One transfer of a model's initialization handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitStep {
+    /// USB control `request` field.
+    pub request: u8,
+    /// USB control `value` field.
+    pub value: u16,
+    /// USB control `index` field.
+    pub index: u16,
+    /// Bytes written by the transfer.
+    pub data: Vec<u8>,
+    /// How the step is performed.
+    pub kind: InitStepKind,
+}
+
+/// How an [`InitStep`] is performed and how its failures are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitStepKind {
+    /// Write, then read a reply that must not be empty. Looks for the
+    /// reply on the regular receive pipe when the control read yields
+    /// nothing. A failed write or a missing reply repeats the step while
+    /// the error is transient ([`Error::is_transient`]), up to `attempts`
+    /// times; initialization fails after that.
+    ///
+    /// [`Error::is_transient`]: crate::Error::is_transient
+    Handshake {
+        /// Writes made before giving up.
+        attempts: usize,
+        /// Timeout of each read (ms).
+        timeout_ms: u64,
+    },
+    /// Write only; a failed write fails initialization.
+    Write,
+    /// Write and optionally read a reply; failures are ignored.
+    BestEffort {
+        /// Timeout of the reply read (ms); `None` skips the read.
+        read_timeout_ms: Option<u64>,
+    },
+    /// Chip command sent with `send` (bulk) instead of a control transfer.
+    /// Its reply is read through the link layer ([`Link`]), so the ACK is
+    /// skipped and an error frame fails the step. Failures fail
+    /// initialization.
+    ///
+    /// [`Link`]: crate::protocol::Link
+    Command {
+        /// Timeout of the reply read (ms); `None` expects no reply.
+        timeout_ms: Option<u64>,
+    },
+}
+
+pub trait DeviceModel: Send + Sync {
     /// Initialize the device via the provided transport. Implementations may
     /// send device-specific sequences (control/interrupt/bulk) necessary to
     /// bring the device to an operational state.
     fn initialize(&self, transport: &mut dyn crate::transport::Transport) -> Result<()>;
 
+    /// The vendor control transfers `initialize` performs, as data, for
+    /// devices that drive an async transport. The default is an empty
+    /// handshake.
+    fn init_steps(&self) -> Vec<InitStep> {
+        Vec::new()
+    }
+
     /// Wrap a raw FeliCa command payload for the device model. The default
     /// implementation returns the original payload unchanged. S330 will
     /// override this to wrap the payload in a PN533 InListPassiveTarget
     /// packet.
     /// Wrap a command for transport. Implementations receive both the
     /// fully-framed FeliCa frame (`framed`) and the raw protocol payload
-    /// (`payload`). By default the framed form is sent unchanged.
-    fn wrap_command(&self, framed: &[u8], _payload: &[u8]) -> Vec<u8> {
+    /// (`payload`), plus the time (ms) the caller gives the card to answer
+    /// for readers that run their own RF timeout. By default the framed
+    /// form is sent unchanged.
+    fn wrap_command(&self, framed: &[u8], _payload: &[u8], _timeout_ms: u64) -> Vec<u8> {
         framed.to_vec()
     }
 
@@ -29,6 +88,14 @@ pub trait DeviceModel {
         Ok(raw.to_vec())
     }
 
+    /// Shortest receive timeout (ms) for a command sent through this
+    /// reader. Chips that retry or time out on their own before answering
+    /// (RCS956, port100) need more than the card's response time. The
+    /// default adds no floor.
+    fn min_timeout_ms(&self) -> u64 {
+        0
+    }
+
     /// Optional model-specific multi-target polling routine. Some device
     /// implementations (e.g. S330/PN533) provide a vendor-control based
     /// InListPassiveTarget operation that can return multiple targets in a
@@ -47,6 +114,18 @@ pub trait DeviceModel {
         Err(crate::Error::PollingFailed)
     }
 
+    /// Set how many times the reader chip itself retries passive target
+    /// activation before answering "no card" (RCS956
+    /// `MxRtyPassiveActivation`); `None` restores the chip default. Models
+    /// without such a setting ignore it.
+    fn set_passive_activation_retries(
+        &self,
+        _transport: &mut dyn crate::transport::Transport,
+        _retries: Option<u8>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Model-specific helper: extract candidate FeliCa wire frames from a
     /// raw device response buffer. Default implementation returns an
     /// empty list which signals no model-specific candidates are
@@ -57,7 +136,199 @@ pub trait DeviceModel {
     }
 }
 
-mod noop;
+/// FeliCa time-slot polling for models that exchange raw FeliCa frames
+/// (S310/S320).
+///
+/// Sends a single Polling command opening `slots` time slots and collects
+/// every Polling response that arrives until the transport times out or
+/// `timeout_ms` elapses. Cards answering in several slots are reported
+/// once, in arrival order.
+pub(crate) fn poll_time_slots(
+    transport: &mut dyn crate::transport::Transport,
+    system_code: crate::types::SystemCode,
+    slots: crate::types::TimeSlots,
+    timeout_ms: u64,
+) -> Result<Vec<crate::card::Card>> {
+    use crate::protocol::{Command, Frame, Response, codec};
+    use crate::types::PollingRequest;
+    use std::time::{Duration, Instant};
+
+    let request = PollingRequest::SystemCode;
+    let cmd = Command::Polling {
+        system_code,
+        request_code: request.request_code(),
+        time_slot: slots.time_slot_byte(),
+    };
+    transport.send(&codec::encode_command_frame(&cmd)?)?;
+
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let mut cards: Vec<crate::card::Card> = Vec::new();
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        // Round up: a 0 ms timeout means "wait forever" to libusb.
+        let raw = match transport.receive(remaining.as_micros().div_ceil(1000) as u64) {
+            Ok(raw) => raw,
+            Err(crate::Error::Timeout) => break,
+            Err(e) => return Err(e),
+        };
+
+        // A single read may carry the responses of several slots.
+        for frame in Frame::split(&raw) {
+            if let Ok(Response::Polling {
+                idm,
+                pmm,
+                request_data,
+            }) = codec::decode_response_frame(0x00, frame)
+                && cards.iter().all(|c| c.idm() != Some(&idm))
+            {
+                let (card, _) =
+                    crate::card::Card::from_polling(idm, pmm, system_code, request, request_data);
+                cards.push(card);
+            }
+        }
+    }
+    Ok(cards)
+}
+
+/// Transfer an [`InitRun`] asks its driver to perform next. The control
+/// transfers use the step's `request`/`value`/`index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InitIo {
+    /// `vendor_control_write` of the step's data.
+    ControlWrite,
+    /// `vendor_control_read` with this timeout (ms).
+    ControlRead(u64),
+    /// `receive` with this timeout (ms).
+    Receive(u64),
+    /// `send` of the step's data.
+    Send,
+    /// Link-layer reply read (`receive_reply`) with this timeout (ms).
+    ReceiveReply(u64),
+}
+
+/// State machine performing one [`InitStep`].
+///
+/// Like [`Link`], it does no I/O itself: the driver performs each
+/// [`InitIo`] returned by [`InitRun::next`] and feeds the outcome back in
+/// (write outcomes carry no bytes), so the blocking and async devices
+/// interpret [`InitStepKind`] the same way.
+///
+/// [`Link`]: crate::protocol::Link
+#[derive(Debug)]
+pub(crate) struct InitRun {
+    kind: InitStepKind,
+    attempt: usize,
+    pending: Option<InitIo>,
+}
+
+impl InitRun {
+    pub(crate) fn new(step: &InitStep) -> Self {
+        Self {
+            kind: step.kind,
+            attempt: 0,
+            pending: None,
+        }
+    }
+
+    /// Outcome of the previous transfer (`None` on the first call) in,
+    /// next transfer out; `Ok(None)` once the step is complete.
+    pub(crate) fn next(&mut self, outcome: Option<Result<Vec<u8>>>) -> Result<Option<InitIo>> {
+        let next = self.advance(outcome)?;
+        self.pending = next;
+        Ok(next)
+    }
+
+    fn advance(&mut self, outcome: Option<Result<Vec<u8>>>) -> Result<Option<InitIo>> {
+        let answered = |o: &Option<Result<Vec<u8>>>| matches!(o, Some(Ok(r)) if !r.is_empty());
+        match (self.kind, self.pending) {
+            (InitStepKind::Handshake { .. }, None) => {
+                self.attempt = 1;
+                Ok(Some(InitIo::ControlWrite))
+            }
+            (InitStepKind::Handshake { timeout_ms, .. }, Some(InitIo::ControlWrite)) => {
+                match outcome {
+                    Some(Err(e)) => self.retry_handshake(e),
+                    _ => Ok(Some(InitIo::ControlRead(timeout_ms))),
+                }
+            }
+            // No reply on the control pipe: look on the receive pipe
+            (InitStepKind::Handshake { timeout_ms, .. }, Some(InitIo::ControlRead(_))) => {
+                if answered(&outcome) {
+                    return Ok(None);
+                }
+                Ok(Some(InitIo::Receive(timeout_ms)))
+            }
+            (InitStepKind::Handshake { .. }, Some(InitIo::Receive(_))) => {
+                if answered(&outcome) {
+                    return Ok(None);
+                }
+                self.retry_handshake(match outcome {
+                    Some(Err(e)) => e,
+                    _ => crate::Error::Timeout,
+                })
+            }
+            (InitStepKind::Write, None) => Ok(Some(InitIo::ControlWrite)),
+            (InitStepKind::BestEffort { .. }, None) => Ok(Some(InitIo::ControlWrite)),
+            (InitStepKind::BestEffort { read_timeout_ms }, Some(InitIo::ControlWrite)) => {
+                Ok(read_timeout_ms.map(InitIo::ControlRead))
+            }
+            (InitStepKind::Command { .. }, None) => Ok(Some(InitIo::Send)),
+            (InitStepKind::Command { timeout_ms }, Some(InitIo::Send)) => {
+                outcome.transpose()?;
+                Ok(timeout_ms.map(InitIo::ReceiveReply))
+            }
+            // Last transfer of the step: best-effort outcomes are dropped,
+            // others must have succeeded
+            (InitStepKind::BestEffort { .. }, Some(_)) => Ok(None),
+            (_, Some(_)) => outcome.transpose().map(|_| None),
+        }
+    }
+
+    /// Start the next handshake attempt after `error` (no reply counts as
+    /// `Error::Timeout`) while attempts remain and the error is transient.
+    fn retry_handshake(&mut self, error: crate::Error) -> Result<Option<InitIo>> {
+        let InitStepKind::Handshake { attempts, .. } = self.kind else {
+            return Err(error);
+        };
+        if self.attempt >= attempts || !error.is_transient() {
+            return Err(error);
+        }
+        self.attempt += 1;
+        Ok(Some(InitIo::ControlWrite))
+    }
+}
+
+/// Run a model's init handshake (see [`DeviceModel::init_steps`]) over a
+/// blocking transport.
+pub(crate) fn run_init_steps(
+    transport: &mut dyn crate::transport::Transport,
+    steps: &[InitStep],
+) -> Result<()> {
+    for step in steps {
+        let mut run = InitRun::new(step);
+        let mut outcome = None;
+        while let Some(io) = run.next(outcome.take())? {
+            outcome = Some(match io {
+                InitIo::ControlWrite => transport
+                    .vendor_control_write(step.request, step.value, step.index, &step.data)
+                    .map(|_| Vec::new()),
+                InitIo::ControlRead(timeout_ms) => {
+                    transport.vendor_control_read(step.request, step.value, step.index, timeout_ms)
+                }
+                InitIo::Receive(timeout_ms) => transport.receive(timeout_ms),
+                InitIo::Send => transport.send(&step.data).map(|_| Vec::new()),
+                InitIo::ReceiveReply(timeout_ms) => {
+                    crate::device::handle::receive_reply(transport, timeout_ms)
+                }
+            });
+        }
+    }
+    Ok(())
+}
+
 // Include the per-device implementations from their directory-style modules.
 // We use `include!` to prefer the new `s310/mod.rs` etc. even if legacy
 // `s310.rs` files exist in the tree. This lets us migrate to the directory
@@ -77,13 +348,17 @@ pub mod s330 {
 }
 pub use s330::S330Model;
 
-/// Factory to create a model implementation for a DeviceType. Unknown
-/// devices receive a no-op model.
+pub mod s380 {
+    include!("s380/mod.rs");
+}
+pub use s380::S380Model;
+
+/// Factory to create a model implementation for a DeviceType.
 pub fn create_model_for(device_type: DeviceType) -> Box<dyn DeviceModel> {
     match device_type {
         DeviceType::S310 => Box::new(s310::S310Model::new()),
         DeviceType::S320 => Box::new(S320Model::new()),
         DeviceType::S330 => Box::new(s330::S330Model::new()),
-        _ => Box::new(noop::NoopModel::new()),
+        DeviceType::S380 => Box::new(S380Model::new()),
     }
 }
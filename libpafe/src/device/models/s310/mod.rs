@@ -14,39 +14,43 @@ impl S310Model {
 
 impl crate::device::models::DeviceModel for S310Model {
     fn initialize(&self, transport: &mut dyn crate::transport::Transport) -> Result<()> {
-        // Conservative S310 init using values from config.
-        for attempt in 0..config::ATTEMPTS {
-            // Use vendor_control_write/read to express explicit USB request
-            // semantics; transports that don't support explicit parameters
-            // will fall back to control_write/control_read via trait defaults.
-            let _ = transport.vendor_control_write(
-                config::INIT_REQUEST,
-                config::INIT_VALUE,
-                config::INIT_INDEX,
-                config::INIT_CMD,
-            )?;
+        super::run_init_steps(transport, &self.init_steps())
+    }
 
-            match transport.vendor_control_read(
-                config::INIT_REQUEST,
-                config::INIT_VALUE,
-                config::INIT_INDEX,
-                config::READ_TIMEOUT_MS,
-            ) {
-                Ok(resp) if !resp.is_empty() => {
-                    return Ok(());
-                }
-                Err(e) => {
-                    if attempt + 1 >= config::ATTEMPTS {
-                        return Err(e);
-                    }
-                }
-                _ => {
-                    // empty response -> retry
-                }
-            }
-        }
+    fn init_steps(&self) -> Vec<crate::device::models::InitStep> {
+        vec![crate::device::models::InitStep {
+            request: config::INIT_REQUEST,
+            value: config::INIT_VALUE,
+            index: config::INIT_INDEX,
+            data: commands::vendor_init().to_vec(),
+            kind: crate::device::models::InitStepKind::Handshake {
+                attempts: config::ATTEMPTS,
+                timeout_ms: config::READ_TIMEOUT_MS,
+            },
+        }]
+    }
 
-        Err(crate::Error::Timeout)
+    /// FeliCa multi-card detection through time-slot polling. `max_targets`
+    /// selects the number of time slots (rounded up to 1/2/4/8/16).
+    fn list_passive_targets(
+        &self,
+        transport: &mut dyn crate::transport::Transport,
+        card_type: crate::types::CardType,
+        system_code: crate::types::SystemCode,
+        max_targets: u8,
+        timeout_ms: u64,
+    ) -> Result<Vec<crate::card::Card>> {
+        if card_type != crate::types::CardType::TypeF {
+            return Err(crate::Error::UnsupportedOperation(format!(
+                "S310 only detects FeliCa (Type F) cards, got {card_type:?}"
+            )));
+        }
+        super::poll_time_slots(
+            transport,
+            system_code,
+            crate::types::TimeSlots::for_targets(max_targets),
+            timeout_ms,
+        )
     }
 }
 
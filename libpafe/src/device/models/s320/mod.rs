@@ -13,62 +13,53 @@ impl S320Model {
 
 impl crate::device::models::DeviceModel for S320Model {
     fn initialize(&self, transport: &mut dyn crate::transport::Transport) -> Result<()> {
-        let mut ok = false;
-        for attempt in 0..config::INIT_ATTEMPTS {
-            // Prefer vendor-specific control transfer with explicit
-            // request/value/index for S320 initialisation. Transports that
-            // do not support explicit parameters will fall back to
-            // `control_write`/`control_read` via the Transport trait
-            // default implementations.
-            let _ = transport.vendor_control_write(
-                config::INIT1_REQUEST,
-                config::INIT1_VALUE,
-                config::INIT1_INDEX,
-                commands::init1(),
-            )?;
-
-            match transport.vendor_control_read(
-                config::INIT1_REQUEST,
-                config::INIT1_VALUE,
-                config::INIT1_INDEX,
-                config::READ_TIMEOUT_MS,
-            ) {
-                Ok(resp) if !resp.is_empty() => {
-                    ok = true;
-                    break;
-                }
-                _ => {
-                    // try interrupt/bulk receive as a fallback
-                    match transport.receive(config::READ_TIMEOUT_MS) {
-                        Ok(resp2) if !resp2.is_empty() => {
-                            ok = true;
-                            break;
-                        }
-                        Err(e2) => {
-                            if attempt + 1 >= config::INIT_ATTEMPTS {
-                                return Err(e2);
-                            }
-                        }
-                        _ => {
-                            // continue retrying
-                        }
-                    }
-                }
-            }
-        }
+        super::run_init_steps(transport, &self.init_steps())
+    }
 
-        if !ok {
-            return Err(crate::Error::Timeout);
-        }
+    fn init_steps(&self) -> Vec<crate::device::models::InitStep> {
+        use crate::device::models::{InitStep, InitStepKind};
+        vec![
+            InitStep {
+                request: config::INIT1_REQUEST,
+                value: config::INIT1_VALUE,
+                index: config::INIT1_INDEX,
+                data: commands::init1().to_vec(),
+                kind: InitStepKind::Handshake {
+                    attempts: config::INIT_ATTEMPTS,
+                    timeout_ms: config::READ_TIMEOUT_MS,
+                },
+            },
+            InitStep {
+                request: config::INIT2_REQUEST,
+                value: config::INIT2_VALUE,
+                index: config::INIT2_INDEX,
+                data: commands::init2().to_vec(),
+                kind: InitStepKind::Write,
+            },
+        ]
+    }
 
-        // Finalize initialization using explicit vendor control write.
-        let _ = transport.vendor_control_write(
-            config::INIT2_REQUEST,
-            config::INIT2_VALUE,
-            config::INIT2_INDEX,
-            commands::init2(),
-        )?;
-        Ok(())
+    /// FeliCa multi-card detection through time-slot polling. `max_targets`
+    /// selects the number of time slots (rounded up to 1/2/4/8/16).
+    fn list_passive_targets(
+        &self,
+        transport: &mut dyn crate::transport::Transport,
+        card_type: crate::types::CardType,
+        system_code: crate::types::SystemCode,
+        max_targets: u8,
+        timeout_ms: u64,
+    ) -> Result<Vec<crate::card::Card>> {
+        if card_type != crate::types::CardType::TypeF {
+            return Err(crate::Error::UnsupportedOperation(format!(
+                "S320 only detects FeliCa (Type F) cards, got {card_type:?}"
+            )));
+        }
+        super::poll_time_slots(
+            transport,
+            system_code,
+            crate::types::TimeSlots::for_targets(max_targets),
+            timeout_ms,
+        )
     }
 }
 
@@ -90,6 +81,28 @@ mod tests {
         assert_eq!(mock.sent[1], vec![0x5C, 0x02]);
     }
 
+    #[test]
+    fn s320_model_init_repeats_unanswered_handshake() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        // First attempt: empty control read, empty receive
+        mock.push_response(Vec::new());
+        mock.push_response(Vec::new());
+        mock.push_response(vec![0xAA]);
+        S320Model::new().initialize(&mut mock).unwrap();
+        assert_eq!(
+            mock.sent,
+            vec![vec![0x5C, 0x01], vec![0x5C, 0x01], vec![0x5C, 0x02]]
+        );
+
+        // Timeouts are transient too, until the attempts run out
+        let mut mock = MockTransport::new(DeviceType::S320);
+        assert!(matches!(
+            S320Model::new().initialize(&mut mock),
+            Err(crate::Error::Timeout)
+        ));
+        assert_eq!(mock.sent.len(), super::config::INIT_ATTEMPTS);
+    }
+
     #[test]
     fn s320_model_uses_vendor_control_parameters() {
         let mut mock = MockTransport::new(DeviceType::S320);
@@ -115,6 +128,64 @@ mod tests {
         assert_eq!(data2, &commands::init2());
     }
 
+    #[test]
+    fn s320_list_passive_targets_rejects_type_a() {
+        let mut mock = MockTransport::new(DeviceType::S320);
+        let model = S320Model::new();
+        match model.list_passive_targets(
+            &mut mock,
+            crate::types::CardType::TypeA,
+            crate::types::SystemCode::ANY,
+            2,
+            100,
+        ) {
+            Err(crate::Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+        assert!(mock.sent.is_empty());
+    }
+
+    #[test]
+    fn s320_list_passive_targets_never_waits_zero_ms() {
+        // Answers every read with nothing after a short delay, so the
+        // polling window runs down through its last millisecond.
+        struct SlowEmptyTransport {
+            timeouts: Vec<u64>,
+        }
+        impl crate::transport::Transport for SlowEmptyTransport {
+            fn send(&mut self, _data: &[u8]) -> Result<()> {
+                Ok(())
+            }
+            fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
+                self.timeouts.push(timeout_ms);
+                std::thread::sleep(std::time::Duration::from_micros(100));
+                Ok(Vec::new())
+            }
+            fn device_type(&self) -> Result<DeviceType> {
+                Ok(DeviceType::S320)
+            }
+            fn reset(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut transport = SlowEmptyTransport {
+            timeouts: Vec::new(),
+        };
+        let cards = S320Model::new()
+            .list_passive_targets(
+                &mut transport,
+                crate::types::CardType::TypeF,
+                crate::types::SystemCode::ANY,
+                1,
+                2,
+            )
+            .unwrap();
+        assert!(cards.is_empty());
+        assert!(!transport.timeouts.is_empty());
+        assert!(!transport.timeouts.contains(&0), "{:?}", transport.timeouts);
+    }
+
     #[test]
     fn s320_model_init_retries_and_fails_on_timeout() {
         let mut mock = MockTransport::new(DeviceType::S320);
@@ -5,12 +5,25 @@
 /// Control read timeout (ms)
 pub const READ_TIMEOUT_MS: u64 = 200;
 
+/// Shortest host receive timeout (ms) for a wrapped command: the RCS956
+/// retries passive activation itself before it answers
+pub const MIN_TIMEOUT_MS: u64 = 1000;
+
 /// RCS956 RF-ON payload
 pub const RCS956_RF_ON: &'static [u8] = &[0xD4u8, 0x32, 0x01, 0x01];
 
 /// RCS956 RF-OFF payload
 pub const RCS956_RF_OFF: &'static [u8] = &[0xD4u8, 0x32, 0x01, 0x00];
 
+/// RCS956 default `MxRtyATR` (RFConfiguration item 0x05)
+pub const RCS956_MX_RTY_ATR_DEFAULT: u8 = 0xFF;
+
+/// RCS956 default `MxRtyPSL` (RFConfiguration item 0x05)
+pub const RCS956_MX_RTY_PSL_DEFAULT: u8 = 0x01;
+
+/// RCS956 default `MxRtyPassiveActivation`: retry forever
+pub const RCS956_MX_RTY_PASSIVE_ACTIVATION_DEFAULT: u8 = 0xFF;
+
 /// RCS956 GetVersion command
 pub const RCS956_GET_VERSION: &'static [u8] = &[0xD4u8, 0x02];
 
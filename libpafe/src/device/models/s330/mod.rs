@@ -16,26 +16,39 @@ impl S330Model {
 
 impl crate::device::models::DeviceModel for S330Model {
     fn initialize(&self, transport: &mut dyn crate::transport::Transport) -> Result<()> {
-        // Prefer explicit vendor control transfers when available. Fall
-        // back to control_write/read via the Transport defaults.
-        // Best-effort RF-ON: some systems/devices may return a Pipe error
-        // for this vendor transfer. Treat it as non-fatal and continue.
-        let _ = transport.vendor_control_write(0x00, 0x0000, 0x0000, commands::rcs956_rf_on());
-
-        // Best-effort read of the RF-ON reply; ignore errors.
-        let _ = transport.vendor_control_read(0x00, 0x0000, 0x0000, config::READ_TIMEOUT_MS);
-
-        // Optionally attempt to query firmware version (best-effort).
-        // Do not perform a blocking read for the version here because a
-        // following operation (e.g. polling) may rely on queued
-        // responses. Keep the write best-effort and avoid consuming the
-        // transport response queue.
-        let _ = transport.vendor_control_write(0x00, 0x0000, 0x0000, commands::rcs956_get_version());
+        super::run_init_steps(transport, &self.init_steps())
+    }
 
-        Ok(())
+    fn init_steps(&self) -> Vec<crate::device::models::InitStep> {
+        use crate::device::models::{InitStep, InitStepKind};
+        vec![
+            // RF-ON and its reply are best-effort: some systems/devices
+            // return a Pipe error for this vendor transfer
+            InitStep {
+                request: 0x00,
+                value: 0x0000,
+                index: 0x0000,
+                data: commands::rcs956_rf_on().to_vec(),
+                kind: InitStepKind::BestEffort {
+                    read_timeout_ms: Some(config::READ_TIMEOUT_MS),
+                },
+            },
+            // Firmware version query, also best-effort. Its reply is not
+            // read: a following operation (e.g. polling) may rely on
+            // queued responses
+            InitStep {
+                request: 0x00,
+                value: 0x0000,
+                index: 0x0000,
+                data: commands::rcs956_get_version().to_vec(),
+                kind: InitStepKind::BestEffort {
+                    read_timeout_ms: None,
+                },
+            },
+        ]
     }
 
-    fn wrap_command(&self, framed: &[u8], payload: &[u8]) -> Vec<u8> {
+    fn wrap_command(&self, framed: &[u8], payload: &[u8], _timeout_ms: u64) -> Vec<u8> {
         // If caller already created a RCS956/PN533-style packet, forward it.
         if !framed.is_empty() && framed[0] == 0xD4 {
             return framed.to_vec();
@@ -82,7 +95,7 @@ impl crate::device::models::DeviceModel for S330Model {
         max_targets: u8,
         timeout_ms: u64,
     ) -> Result<Vec<crate::card::Card>> {
-        use crate::types::CardType;
+        use crate::types::{CardType, PollingRequest};
 
         // Determine brty (bit rate/type) parameter based on card type
         let brty = match card_type {
@@ -95,7 +108,11 @@ impl crate::device::models::DeviceModel for S330Model {
 
         // For FeliCa (Type F), add the polling payload
         if card_type == CardType::TypeF {
-            let payload = crate::protocol::commands::polling::encode_polling(system_code, 0, 0);
+            let payload = crate::protocol::commands::polling::encode_polling(
+                system_code,
+                PollingRequest::SystemCode.request_code(),
+                0,
+            );
             cmd.extend_from_slice(&[0xFF, 0xFF, 0x00, 0x00]);
             cmd.extend_from_slice(&payload);
         }
@@ -167,10 +184,19 @@ impl crate::device::models::DeviceModel for S330Model {
                     if let crate::protocol::Response::Polling {
                         idm,
                         pmm,
-                        system_code,
+                        request_data,
                     } = resp
                     {
-                        out.push(crate::card::Card::new(idm, pmm, system_code));
+                        out.push(
+                            crate::card::Card::from_polling(
+                                idm,
+                                pmm,
+                                system_code,
+                                PollingRequest::SystemCode,
+                                request_data,
+                            )
+                            .0,
+                        );
                     }
                 }
                 #[cfg_attr(not(test), allow(unused_variables))]
@@ -193,10 +219,19 @@ impl crate::device::models::DeviceModel for S330Model {
                                 if let crate::protocol::Response::Polling {
                                     idm,
                                     pmm,
-                                    system_code,
+                                    request_data,
                                 } = resp2
                                 {
-                                    out.push(crate::card::Card::new(idm, pmm, system_code));
+                                    out.push(
+                                        crate::card::Card::from_polling(
+                                            idm,
+                                            pmm,
+                                            system_code,
+                                            PollingRequest::SystemCode,
+                                            request_data,
+                                        )
+                                        .0,
+                                    );
                                     continue;
                                 }
                             }
@@ -213,10 +248,19 @@ impl crate::device::models::DeviceModel for S330Model {
                             if let crate::protocol::Response::Polling {
                                 idm,
                                 pmm,
-                                system_code,
+                                request_data,
                             } = resp2
                             {
-                                out.push(crate::card::Card::new(idm, pmm, system_code));
+                                out.push(
+                                    crate::card::Card::from_polling(
+                                        idm,
+                                        pmm,
+                                        system_code,
+                                        PollingRequest::SystemCode,
+                                        request_data,
+                                    )
+                                    .0,
+                                );
                                 continue;
                             }
                         }
@@ -233,6 +277,27 @@ impl crate::device::models::DeviceModel for S330Model {
         Ok(out)
     }
 
+    fn min_timeout_ms(&self) -> u64 {
+        config::MIN_TIMEOUT_MS
+    }
+
+    fn set_passive_activation_retries(
+        &self,
+        transport: &mut dyn crate::transport::Transport,
+        retries: Option<u8>,
+    ) -> Result<()> {
+        let retries = retries.unwrap_or(config::RCS956_MX_RTY_PASSIVE_ACTIVATION_DEFAULT);
+        transport.vendor_control_write(
+            0x00,
+            0x0000,
+            0x0000,
+            &rcs956::build_max_retries(retries),
+        )?;
+        // Best-effort read of the RFConfiguration reply
+        let _ = transport.vendor_control_read(0x00, 0x0000, 0x0000, config::READ_TIMEOUT_MS);
+        Ok(())
+    }
+
     fn extract_candidate_frames(&self, raw: &[u8], expected_cmd: u8) -> Vec<Vec<u8>> {
         rcs956::extract_all_felica_frames_from_pn532_response(raw, expected_cmd)
     }
@@ -270,6 +335,26 @@ mod tests {
         assert_eq!(m.sent[1], vec![0xD4, 0x44, 0x01]);
     }
 
+    #[test]
+    fn s330_sets_passive_activation_retries() {
+        let mut m = MockTransport::new(DeviceType::S330);
+        m.push_response(vec![0xD5, 0x33]);
+        S330Model::new()
+            .set_passive_activation_retries(&mut m, Some(0x0A))
+            .unwrap();
+        S330Model::new()
+            .set_passive_activation_retries(&mut m, None)
+            .unwrap();
+        assert_eq!(
+            m.sent,
+            vec![
+                vec![0xD4, 0x32, 0x05, 0xFF, 0x01, 0x0A],
+                vec![0xD4, 0x32, 0x05, 0xFF, 0x01, 0xFF],
+            ]
+        );
+        assert!(m.responses.is_empty());
+    }
+
     #[test]
     fn s330_in_list_passive_target_builder() {
         let v = super::rcs956::build_in_list_passive_target(1, 0x00);
@@ -25,6 +25,19 @@ pub fn build_in_list_passive_target(max_targets: u8, brty: u8) -> Vec<u8> {
     vec![0xD4, 0x4A, max_targets, brty]
 }
 
+/// Build an RFConfiguration "MaxRetries" (item 0x05) payload keeping the
+/// default ATR/PSL retries and setting `MxRtyPassiveActivation`.
+pub fn build_max_retries(passive_activation: u8) -> Vec<u8> {
+    vec![
+        0xD4,
+        0x32,
+        0x05,
+        super::super::config::RCS956_MX_RTY_ATR_DEFAULT,
+        super::super::config::RCS956_MX_RTY_PSL_DEFAULT,
+        passive_activation,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,6 +54,14 @@ mod tests {
         assert_eq!(build_get_version(), config::RCS956_GET_VERSION); // Note: constant name kept for compatibility
     }
 
+    #[test]
+    fn build_max_retries_sets_passive_activation() {
+        assert_eq!(
+            build_max_retries(0x0A),
+            vec![0xD4, 0x32, 0x05, 0xFF, 0x01, 0x0A]
+        );
+    }
+
     #[test]
     fn build_in_list_passive_target_builds_vector() {
         let v = build_in_list_passive_target(1, 0x00);
@@ -6,7 +6,7 @@ mod builders;
 mod extractor;
 mod multi_frame;
 
-pub use builders::{build_in_list_passive_target, build_rf_on};
+pub use builders::{build_in_list_passive_target, build_max_retries, build_rf_on};
 pub use extractor::extract_felica_from_pn532_response;
 pub use multi_frame::extract_all_felica_frames_from_pn532_response;
 
@@ -385,7 +385,7 @@ mod tests {
                 if let crate::protocol::Response::Polling {
                     idm,
                     pmm,
-                    system_code,
+                    request_data,
                 } = resp
                 {
                     assert_eq!(
@@ -396,7 +396,7 @@ mod tests {
                         pmm.as_bytes(),
                         &[0x23, 0xAA, 0x1F, 0x01, 0x36, 0x42, 0x82, 0x47]
                     );
-                    assert_eq!(system_code.as_u16(), 0x9A45);
+                    assert_eq!(request_data, Some([0x45, 0x9A]));
                     decoded = true;
                     break;
                 }
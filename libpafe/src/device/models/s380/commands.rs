@@ -0,0 +1,58 @@
+// libpafe-rs/libpafe/src/device/models/s380/commands.rs
+
+//! S380 (port100) command frame builders
+
+use super::config;
+use super::port100::encode_frame;
+
+/// SetCommandType: select command set `kind` (1 for host-driven RF).
+pub fn set_command_type(kind: u8) -> Vec<u8> {
+    encode_frame(config::SET_COMMAND_TYPE, &[kind])
+}
+
+/// GetFirmwareVersion
+pub fn get_firmware_version() -> Vec<u8> {
+    encode_frame(config::GET_FIRMWARE_VERSION, &[])
+}
+
+/// SwitchRF: turn the RF field on or off.
+pub fn switch_rf(on: bool) -> Vec<u8> {
+    encode_frame(config::SWITCH_RF, &[on as u8])
+}
+
+/// InSetRF configured for FeliCa 212 kbps.
+pub fn in_set_rf_212f() -> Vec<u8> {
+    encode_frame(config::IN_SET_RF, config::IN_SET_RF_212F)
+}
+
+/// InSetProtocol with the given item/value pairs.
+pub fn in_set_protocol(settings: &[u8]) -> Vec<u8> {
+    encode_frame(config::IN_SET_PROTOCOL, settings)
+}
+
+/// InCommRF carrying a FeliCa command `payload` (without its length
+/// byte), waiting up to `timeout_ms` for the card.
+pub fn in_comm_rf(timeout_ms: u64, payload: &[u8]) -> Vec<u8> {
+    // Timeout in units of 0.1 ms, rounded up by one millisecond
+    let units = (timeout_ms.saturating_add(1).saturating_mul(10)).min(0xFFFF) as u16;
+    let mut data = Vec::with_capacity(3 + payload.len());
+    data.extend_from_slice(&units.to_le_bytes());
+    data.push((payload.len() + 1) as u8);
+    data.extend_from_slice(payload);
+    encode_frame(config::IN_COMM_RF, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_comm_rf_prefixes_timeout_and_length() {
+        let frame = in_comm_rf(100, &[0x06, 0x00, 0xff, 0xff, 0x01, 0x00]);
+        // 00 00 FF FF FF LEN(2) LCS D6 04 TIMEOUT(2) LEN ...
+        assert_eq!(&frame[8..10], &[0xD6, 0x04]);
+        assert_eq!(&frame[10..12], &1010u16.to_le_bytes());
+        assert_eq!(frame[12], 7);
+        assert_eq!(&frame[13..19], &[0x06, 0x00, 0xff, 0xff, 0x01, 0x00]);
+    }
+}
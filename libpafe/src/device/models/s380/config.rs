@@ -0,0 +1,40 @@
+// libpafe-rs/libpafe/src/device/models/s380/config.rs
+
+//! S380-specific configuration
+
+/// Timeout (ms) for the reply to a port100 chip command
+pub const REPLY_TIMEOUT_MS: u64 = 100;
+
+/// Longest RF timeout (ms) InCommRF gets within the shortest receive
+/// window (see `S380Model::min_timeout_ms`)
+pub const IN_COMM_RF_TIMEOUT_MS: u64 = 100;
+
+/// port100 host->device command prefix
+pub const CMD_PREFIX_HOST: u8 = 0xD6;
+/// port100 device->host response prefix
+pub const CMD_PREFIX_DEVICE: u8 = 0xD7;
+
+/// port100 command codes (responses use code + 1)
+pub const IN_SET_RF: u8 = 0x00;
+pub const IN_SET_PROTOCOL: u8 = 0x02;
+pub const IN_COMM_RF: u8 = 0x04;
+pub const SWITCH_RF: u8 = 0x06;
+pub const GET_FIRMWARE_VERSION: u8 = 0x20;
+pub const SET_COMMAND_TYPE: u8 = 0x2A;
+
+/// InSetRF settings for FeliCa 212 kbps (send and receive)
+pub const IN_SET_RF_212F: &[u8] = &[0x01, 0x01, 0x0F, 0x01];
+
+/// InSetProtocol defaults (item/value pairs) applied before selecting a
+/// card technology
+pub const IN_SET_PROTOCOL_DEFAULTS: &[u8] = &[
+    0x00, 0x18, 0x01, 0x01, 0x02, 0x01, 0x03, 0x00, 0x04, 0x00, 0x05, 0x00, 0x06, 0x00, 0x07, 0x08,
+    0x08, 0x00, 0x09, 0x00, 0x0A, 0x00, 0x0B, 0x00, 0x0C, 0x00, 0x0E, 0x04, 0x0F, 0x00, 0x10, 0x00,
+    0x11, 0x00, 0x12, 0x00, 0x13, 0x06,
+];
+
+/// InSetProtocol for FeliCa: initial guard time of 24 units
+pub const IN_SET_PROTOCOL_212F: &[u8] = &[0x00, 0x18];
+
+/// InCommRF status reported when no card answered in time
+pub const IN_COMM_RF_RECEIVE_TIMEOUT: u32 = 0x0000_0080;
@@ -0,0 +1,267 @@
+// libpafe-rs/libpafe/src/device/models/s380/mod.rs
+
+mod commands;
+mod config;
+mod port100;
+
+use crate::Result;
+
+/// RC-S380 model speaking the port100 command set. FeliCa commands are
+/// carried by InCommRF; the chip is configured for 212 kbps FeliCa during
+/// initialization.
+#[derive(Debug, Default)]
+pub struct S380Model;
+
+impl S380Model {
+    /// Create the model; the reader is configured by `initialize`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// InCommRF timeout for a command the host waits `timeout_ms` for. Past
+/// the `min_timeout_ms` floor the chip's own reply time is taken off, so
+/// its answer always arrives within the host's receive window.
+fn rf_timeout_ms(timeout_ms: u64) -> u64 {
+    timeout_ms.min(
+        timeout_ms
+            .saturating_sub(config::REPLY_TIMEOUT_MS)
+            .max(config::IN_COMM_RF_TIMEOUT_MS),
+    )
+}
+
+impl crate::device::models::DeviceModel for S380Model {
+    fn initialize(&self, transport: &mut dyn crate::transport::Transport) -> Result<()> {
+        super::run_init_steps(transport, &self.init_steps())
+    }
+
+    fn init_steps(&self) -> Vec<crate::device::models::InitStep> {
+        use crate::device::models::{InitStep, InitStepKind};
+        let reply = InitStepKind::Command {
+            timeout_ms: Some(config::REPLY_TIMEOUT_MS),
+        };
+        let step = |data: Vec<u8>, kind| InitStep {
+            request: 0x00,
+            value: 0x0000,
+            index: 0x0000,
+            data,
+            kind,
+        };
+        vec![
+            // An ACK aborts whatever command the chip may still be running
+            step(
+                crate::constants::LINK_ACK_FRAME.to_vec(),
+                InitStepKind::Command { timeout_ms: None },
+            ),
+            step(commands::set_command_type(1), reply),
+            step(commands::get_firmware_version(), reply),
+            step(commands::switch_rf(false), reply),
+            step(commands::in_set_rf_212f(), reply),
+            step(
+                commands::in_set_protocol(config::IN_SET_PROTOCOL_DEFAULTS),
+                reply,
+            ),
+            step(
+                commands::in_set_protocol(config::IN_SET_PROTOCOL_212F),
+                reply,
+            ),
+        ]
+    }
+
+    fn wrap_command(&self, framed: &[u8], payload: &[u8], timeout_ms: u64) -> Vec<u8> {
+        // Already a port100 command frame: forward it.
+        if port100::is_extended_frame(framed) {
+            return framed.to_vec();
+        }
+        commands::in_comm_rf(rf_timeout_ms(timeout_ms), payload)
+    }
+
+    fn unwrap_response(&self, _expected_cmd: u8, raw: &[u8]) -> Result<Vec<u8>> {
+        // Hand the FeliCa response to the protocol decoder as a wire frame
+        crate::protocol::Frame::encode(&port100::in_comm_rf_payload(raw)?)
+    }
+
+    fn min_timeout_ms(&self) -> u64 {
+        // The chip waits out its own RF timeout before it replies
+        config::IN_COMM_RF_TIMEOUT_MS + config::REPLY_TIMEOUT_MS
+    }
+
+    /// FeliCa detection through one single-slot InCommRF Polling. The chip
+    /// passes on the first response only, so asking for more than one
+    /// target is rejected.
+    fn list_passive_targets(
+        &self,
+        transport: &mut dyn crate::transport::Transport,
+        card_type: crate::types::CardType,
+        system_code: crate::types::SystemCode,
+        max_targets: u8,
+        timeout_ms: u64,
+    ) -> Result<Vec<crate::card::Card>> {
+        use crate::protocol::{Response, codec};
+        use crate::types::{CardType, PollingRequest, TimeSlots};
+
+        if card_type != CardType::TypeF {
+            return Err(crate::Error::UnsupportedOperation(format!(
+                "S380 model only detects FeliCa (Type F) cards, got {card_type:?}"
+            )));
+        }
+        if max_targets > 1 {
+            return Err(crate::Error::UnsupportedOperation(format!(
+                "S380 model detects one card at a time, got max_targets {max_targets}"
+            )));
+        }
+        let request = PollingRequest::SystemCode;
+        let payload = crate::protocol::commands::polling::encode_polling(
+            system_code,
+            request.request_code(),
+            TimeSlots::One.time_slot_byte(),
+        );
+        transport.send(&commands::in_comm_rf(timeout_ms, &payload))?;
+        let raw =
+            crate::device::handle::receive_reply(transport, timeout_ms + config::REPLY_TIMEOUT_MS)?;
+
+        let frame = self.unwrap_response(0x00, &raw)?;
+        match codec::decode_response_frame(0x00, &frame)? {
+            Response::Polling {
+                idm,
+                pmm,
+                request_data,
+            } => {
+                let (card, _) =
+                    crate::card::Card::from_polling(idm, pmm, system_code, request, request_data);
+                Ok(vec![card])
+            }
+            _ => Err(crate::Error::PollingFailed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::Device;
+    use crate::device::models::DeviceModel;
+    use crate::transport::mock::MockTransport;
+    use crate::types::{DeviceType, Idm, SystemCode};
+    use crate::utils::parse_hex;
+
+    const ACK: &str = "0000ff00ff00";
+    /// Replies captured from an RC-S380/S during initialization, in order:
+    /// SetCommandType, GetFirmwareVersion (1.11), SwitchRF, InSetRF and
+    /// InSetProtocol twice.
+    const INIT_REPLIES: [&str; 6] = [
+        "0000ffffff0300fdd72b00fe00",
+        "0000ffffff0400fcd7211101f600",
+        "0000ffffff0300fdd707002200",
+        "0000ffffff0300fdd701002800",
+        "0000ffffff0300fdd703002600",
+        "0000ffffff0300fdd703002600",
+    ];
+    /// InCommRF reply carrying a Polling response
+    const POLLING_REPLY: &str = "0000ffffff1b00e5d70500000000001401012e4c901a0b3c5d\
+                                 100b4b428485d0ff0003c300";
+    /// InCommRF reply when no card answered
+    const NO_CARD_REPLY: &str = "0000ffffff0700f9d7058000000000a400";
+
+    fn seeded(replies: &[&str]) -> MockTransport {
+        let mut mock = MockTransport::new(DeviceType::S380);
+        for reply in INIT_REPLIES {
+            mock.push_response(parse_hex(ACK).unwrap());
+            mock.push_response(parse_hex(reply).unwrap());
+        }
+        for reply in replies {
+            mock.push_response(parse_hex(ACK).unwrap());
+            mock.push_response(parse_hex(reply).unwrap());
+        }
+        mock
+    }
+
+    #[test]
+    fn s380_init_sends_port100_sequence() {
+        let mut mock = seeded(&[]);
+        S380Model::new().initialize(&mut mock).unwrap();
+
+        assert_eq!(mock.sent.len(), 7);
+        assert_eq!(mock.sent[0], parse_hex(ACK).unwrap());
+        // D6 <code> of each command, in order
+        let codes: Vec<u8> = mock.sent[1..].iter().map(|f| f[9]).collect();
+        assert_eq!(codes, vec![0x2A, 0x20, 0x06, 0x00, 0x02, 0x02]);
+        assert!(mock.responses.is_empty());
+    }
+
+    #[test]
+    fn s380_device_polls_through_in_comm_rf() {
+        let mock = seeded(&[POLLING_REPLY, NO_CARD_REPLY]);
+        let mut dev = Device::new_with_transport(Box::new(mock))
+            .unwrap()
+            .with_retry_policy(crate::device::RetryPolicy::none())
+            .initialize()
+            .unwrap();
+
+        let card = dev.polling(SystemCode::new(0x0003)).unwrap();
+        assert_eq!(
+            card.idm(),
+            Some(&Idm::from_bytes([
+                0x01, 0x2e, 0x4c, 0x90, 0x1a, 0x0b, 0x3c, 0x5d
+            ]))
+        );
+        assert!(matches!(
+            dev.polling(SystemCode::new(0x0003)),
+            Err(crate::Error::Timeout)
+        ));
+    }
+
+    #[test]
+    fn s380_wrap_command_passes_the_command_timeout() {
+        let model = S380Model::new();
+        let payload = [0x06, 0x01, 0x02];
+        for (timeout_ms, rf_ms) in [(20, 20), (150, 100), (1000, 900)] {
+            assert_eq!(
+                model.wrap_command(&[], &payload, timeout_ms),
+                commands::in_comm_rf(rf_ms, &payload)
+            );
+            // The chip answers before the host stops waiting
+            assert!(rf_ms + config::REPLY_TIMEOUT_MS <= timeout_ms.max(model.min_timeout_ms()));
+        }
+    }
+
+    #[test]
+    fn s380_list_passive_targets_polls_one_slot() {
+        let mut mock = seeded(&[POLLING_REPLY]);
+        let model = S380Model::new();
+        model.initialize(&mut mock).unwrap();
+        let cards = model
+            .list_passive_targets(
+                &mut mock,
+                crate::types::CardType::TypeF,
+                SystemCode::new(0xffff),
+                1,
+                100,
+            )
+            .unwrap();
+        assert_eq!(cards.len(), 1);
+        // A single time slot requested in the Polling payload
+        let sent = mock.sent.last().unwrap();
+        assert_eq!(&sent[13..18], &[0x00, 0xff, 0xff, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn s380_list_passive_targets_rejects_multiple_targets() {
+        let mut mock = seeded(&[]);
+        let model = S380Model::new();
+        model.initialize(&mut mock).unwrap();
+        let sent_before = mock.sent.len();
+        match model.list_passive_targets(
+            &mut mock,
+            crate::types::CardType::TypeF,
+            SystemCode::new(0xffff),
+            4,
+            100,
+        ) {
+            Err(crate::Error::UnsupportedOperation(_)) => {}
+            other => panic!("expected UnsupportedOperation, got {:?}", other),
+        }
+        // Rejected without polling
+        assert_eq!(mock.sent.len(), sent_before);
+    }
+}
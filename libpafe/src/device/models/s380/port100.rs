@@ -0,0 +1,185 @@
+// libpafe-rs/libpafe/src/device/models/s380/port100.rs
+
+//! port100 framing used by the RC-S380.
+//!
+//! Every command travels in an extended frame with a little-endian
+//! length: `00 00 FF FF FF LENL LENH LCS D6 <code> <data> DCS 00`. The
+//! chip first answers with an ACK frame, then with `D7 <code + 1> <data>`
+//! in the same framing.
+
+use super::config;
+use crate::protocol::{dcs, lcs};
+use crate::{Error, Result};
+
+/// Extended frame start: preamble followed by the `FF FF` marker.
+const EXTENDED_START: [u8; 5] = [0x00, 0x00, 0xFF, 0xFF, 0xFF];
+
+/// Header (start, length, LCS) plus DCS and postamble.
+const FRAME_OVERHEAD: usize = 10;
+
+/// Build the extended frame for command `code` with `data`.
+pub fn encode_frame(code: u8, data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(2 + data.len());
+    body.push(config::CMD_PREFIX_HOST);
+    body.push(code);
+    body.extend_from_slice(data);
+
+    let len = (body.len() as u16).to_le_bytes();
+    let mut out = Vec::with_capacity(FRAME_OVERHEAD + body.len());
+    out.extend_from_slice(&EXTENDED_START);
+    out.extend_from_slice(&len);
+    out.push(lcs(len[0].wrapping_add(len[1])));
+    out.extend_from_slice(&body);
+    out.push(dcs(&body));
+    out.push(crate::constants::FELICA_POSTAMBLE);
+    out
+}
+
+/// Whether `raw` is an extended frame (as opposed to an ACK or a FeliCa
+/// frame prepared by the caller).
+pub fn is_extended_frame(raw: &[u8]) -> bool {
+    raw.starts_with(&EXTENDED_START)
+}
+
+/// Payload (`D7 ...`) of the extended frame in `raw`, skipping a leading
+/// ACK frame.
+pub fn decode_frame(raw: &[u8]) -> Result<Vec<u8>> {
+    let raw = raw
+        .strip_prefix(&crate::constants::LINK_ACK_FRAME[..])
+        .unwrap_or(raw);
+    if raw.len() < FRAME_OVERHEAD {
+        return Err(Error::InvalidLength {
+            expected: FRAME_OVERHEAD,
+            actual: raw.len(),
+        });
+    }
+    if !is_extended_frame(raw) {
+        return Err(Error::FrameFormat(
+            "expected a port100 extended frame".into(),
+        ));
+    }
+
+    let lcs_expected = lcs(raw[5].wrapping_add(raw[6]));
+    if raw[7] != lcs_expected {
+        return Err(Error::ChecksumMismatch {
+            expected: lcs_expected,
+            actual: raw[7],
+        });
+    }
+    let len = u16::from_le_bytes([raw[5], raw[6]]) as usize;
+    if raw.len() < FRAME_OVERHEAD + len {
+        return Err(Error::InvalidLength {
+            expected: FRAME_OVERHEAD + len,
+            actual: raw.len(),
+        });
+    }
+
+    let body = &raw[8..8 + len];
+    let dcs_expected = dcs(body);
+    if raw[8 + len] != dcs_expected {
+        return Err(Error::ChecksumMismatch {
+            expected: dcs_expected,
+            actual: raw[8 + len],
+        });
+    }
+    Ok(body.to_vec())
+}
+
+/// Data of the response to command `code`, checking the `D7 <code + 1>`
+/// header.
+pub fn decode_response(code: u8, raw: &[u8]) -> Result<Vec<u8>> {
+    let body = decode_frame(raw)?;
+    match body.as_slice() {
+        [config::CMD_PREFIX_DEVICE, rsp, data @ ..] if *rsp == code.wrapping_add(1) => {
+            Ok(data.to_vec())
+        }
+        [_, rsp, ..] => Err(Error::UnexpectedResponse {
+            expected: code.wrapping_add(1),
+            actual: *rsp,
+        }),
+        _ => Err(Error::InvalidLength {
+            expected: 2,
+            actual: body.len(),
+        }),
+    }
+}
+
+/// FeliCa payload carried by an InCommRF response, without its length
+/// byte. A receive timeout status means no card answered.
+pub fn in_comm_rf_payload(raw: &[u8]) -> Result<Vec<u8>> {
+    let data = decode_response(config::IN_COMM_RF, raw)?;
+    if data.len() < 4 {
+        return Err(Error::InvalidLength {
+            expected: 4,
+            actual: data.len(),
+        });
+    }
+    let status = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    if status == config::IN_COMM_RF_RECEIVE_TIMEOUT {
+        return Err(Error::Timeout);
+    }
+    if status != 0 {
+        return Err(Error::FrameFormat(format!(
+            "InCommRF failed with status {status:#010x}"
+        )));
+    }
+
+    // data[4] is reserved; data[5] is the FeliCa length byte
+    let felica = data.get(5..).unwrap_or_default();
+    let len = felica.first().copied().unwrap_or(0) as usize;
+    if len == 0 || len > felica.len() {
+        return Err(Error::InvalidLength {
+            expected: len,
+            actual: felica.len(),
+        });
+    }
+    Ok(felica[1..len].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse_hex;
+
+    #[test]
+    fn encode_matches_captured_frame() {
+        // GetFirmwareVersion as sent by the vendor driver
+        assert_eq!(
+            encode_frame(config::GET_FIRMWARE_VERSION, &[]),
+            parse_hex("0000ffffff0200fed6200a00").unwrap()
+        );
+    }
+
+    #[test]
+    fn decode_response_skips_ack() {
+        let raw = parse_hex("0000ff00ff00 0000ffffff0400fcd7211101f600").unwrap();
+        assert_eq!(
+            decode_response(config::GET_FIRMWARE_VERSION, &raw).unwrap(),
+            vec![0x11, 0x01]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_bad_checksum_and_wrong_response() {
+        let mut raw = parse_hex("0000ffffff0300fdd707002200").unwrap();
+        assert!(decode_response(config::SWITCH_RF, &raw).is_ok());
+        assert!(matches!(
+            decode_response(config::IN_SET_RF, &raw),
+            Err(Error::UnexpectedResponse {
+                expected: 0x01,
+                actual: 0x07
+            })
+        ));
+        raw[10] ^= 0xFF;
+        assert!(matches!(
+            decode_frame(&raw),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn in_comm_rf_receive_timeout_means_no_card() {
+        let raw = parse_hex("0000ffffff0700f9d7058000000000a400").unwrap();
+        assert!(matches!(in_comm_rf_payload(&raw), Err(Error::Timeout)));
+    }
+}
@@ -0,0 +1,217 @@
+// libpafe-rs/libpafe/src/device/reader.rs
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::card::Card;
+use crate::device::{Device, Initialized};
+use crate::protocol::{Command, Response};
+use crate::types::{BlockData, BlockElement, ServiceCode, SystemCode};
+use crate::{Error, Result};
+
+/// Time a request may spend queued and running unless overridden with
+/// [`ReaderHandle::timeout`].
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request body run on the reader thread; it sends its own reply.
+type Work = Box<dyn FnOnce(&mut Device<Initialized>) + Send>;
+
+/// Work queued for the reader thread.
+struct Job {
+    /// After this instant nobody waits for the result any more.
+    deadline: Instant,
+    run: Work,
+}
+
+/// Cloneable, `Send` handle to a device owned by a dedicated thread.
+///
+/// `Device` is not `Send`, so the thread opens it itself through the
+/// closure passed to [`ReaderHandle::spawn`]. Requests from all handles
+/// go through one queue and run one at a time in arrival order. Each
+/// request waits at most the handle's timeout (5 s by default); a request
+/// that is still queued when its caller gave up is skipped. The thread
+/// ends once every handle is dropped.
+#[derive(Clone)]
+pub struct ReaderHandle {
+    jobs: mpsc::Sender<Job>,
+    timeout: Duration,
+}
+
+impl ReaderHandle {
+    /// Start the reader thread and open the device on it with `open`.
+    /// Errors from `open` are returned here.
+    pub fn spawn<F>(open: F) -> Result<Self>
+    where
+        F: FnOnce() -> Result<Device<Initialized>> + Send + 'static,
+    {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (ready_tx, ready) = mpsc::channel();
+        thread::Builder::new()
+            .name("libpafe-reader".into())
+            .spawn(move || {
+                let mut device = match open() {
+                    Ok(device) => {
+                        let _ = ready_tx.send(Ok(()));
+                        device
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                for job in queue {
+                    if Instant::now() < job.deadline {
+                        (job.run)(&mut device);
+                    }
+                }
+            })
+            .map_err(|_| Error::ReaderClosed)?;
+
+        ready.recv().map_err(|_| Error::ReaderClosed)??;
+        Ok(Self {
+            jobs,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+        })
+    }
+
+    /// A handle to the same reader whose requests wait at most `timeout`.
+    pub fn timeout(&self, timeout: Duration) -> Self {
+        Self {
+            jobs: self.jobs.clone(),
+            timeout,
+        }
+    }
+
+    /// Run `f` on the reader thread with exclusive access to the device
+    /// and return its result. Fails with `Error::Timeout` when the result
+    /// is not available within the handle's timeout; a request already
+    /// running at that point still completes on the device. A panic in
+    /// `f` is caught on the reader thread and returned as
+    /// `Error::RequestPanicked`.
+    pub fn request<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Device<Initialized>) -> Result<T> + Send + 'static,
+    {
+        let deadline = Instant::now() + self.timeout;
+        let (reply, result) = mpsc::channel();
+        let job = Job {
+            deadline,
+            run: Box::new(move |device| {
+                // A panicking request must neither take the reader thread
+                // down for the other handles nor leave its caller waiting
+                let result = panic::catch_unwind(AssertUnwindSafe(|| f(device)))
+                    .unwrap_or(Err(Error::RequestPanicked));
+                let _ = reply.send(result);
+            }),
+        };
+        self.jobs.send(job).map_err(|_| Error::ReaderClosed)?;
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match result.recv_timeout(remaining) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(Error::Timeout),
+            // The job was skipped because its deadline passed while queued
+            Err(mpsc::RecvTimeoutError::Disconnected) if Instant::now() >= deadline => {
+                Err(Error::Timeout)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(Error::ReaderClosed),
+        }
+    }
+
+    /// Poll for a FeliCa card (see [`Device::polling`]).
+    pub fn poll(&self, system_code: SystemCode) -> Result<Card> {
+        self.request(move |device| device.polling(system_code))
+    }
+
+    /// Read blocks from `card` (see [`Card::read_blocks`]).
+    pub fn read_blocks(
+        &self,
+        card: &Card,
+        services: &[ServiceCode],
+        blocks: &[BlockElement],
+    ) -> Result<Vec<BlockData>> {
+        let card = card.clone();
+        let services = services.to_vec();
+        let blocks = blocks.to_vec();
+        self.request(move |device| card.read_blocks(device, &services, &blocks))
+    }
+
+    /// Write blocks of one service to `card` (see [`Card::write_blocks`]).
+    pub fn write_blocks(
+        &self,
+        card: &Card,
+        service: ServiceCode,
+        blocks: &[(BlockElement, BlockData)],
+    ) -> Result<()> {
+        let card = card.clone();
+        let blocks = blocks.to_vec();
+        self.request(move |device| card.write_blocks(device, service, &blocks))
+    }
+
+    /// Execute a raw command (see [`Device::execute`]).
+    pub fn execute(&self, cmd: Command, timeout_ms: u64) -> Result<Response> {
+        self.request(move |device| device.execute(cmd, timeout_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, mock_device, polling_frame};
+    use crate::types::Idm;
+
+    fn spawn_with(frames: Vec<Vec<u8>>) -> ReaderHandle {
+        ReaderHandle::spawn(move || Ok(mock_device(frames))).unwrap()
+    }
+
+    #[test]
+    fn handles_share_one_device_across_threads() {
+        let reader = spawn_with(vec![polling_frame(TEST_IDM), polling_frame(TEST_IDM)]);
+        let other = reader.clone();
+        let worker = thread::spawn(move || other.poll(SystemCode::new(0xffff)));
+
+        let card = reader.poll(SystemCode::new(0xffff)).unwrap();
+        assert_eq!(card.idm(), Some(&Idm::from_bytes(TEST_IDM)));
+        assert!(worker.join().unwrap().is_ok());
+        // Both frames were consumed: nothing is left for a third poll
+        assert!(reader.poll(SystemCode::new(0xffff)).is_err());
+    }
+
+    #[test]
+    fn request_times_out_and_queued_work_is_skipped() {
+        let reader = spawn_with(vec![]);
+        let slow = reader.clone();
+        let busy = thread::spawn(move || {
+            slow.request(|_| {
+                thread::sleep(Duration::from_millis(100));
+                Ok(())
+            })
+        });
+        thread::sleep(Duration::from_millis(20));
+
+        let short = reader.timeout(Duration::from_millis(10));
+        let skipped = short.request(|_| -> Result<()> { panic!("expired request must not run") });
+        assert!(matches!(skipped, Err(Error::Timeout)));
+        assert!(busy.join().unwrap().is_ok());
+        // The reader keeps serving requests after skipping one
+        assert_eq!(reader.request(|_| Ok(7)).unwrap(), 7);
+    }
+
+    #[test]
+    fn panicking_request_fails_only_its_caller() {
+        let reader = spawn_with(vec![polling_frame(TEST_IDM)]);
+        let r = reader.request(|_| -> Result<()> { panic!("request bug") });
+        assert!(matches!(r, Err(Error::RequestPanicked)));
+        // The device is still served on the same thread
+        assert!(reader.poll(SystemCode::new(0xffff)).is_ok());
+    }
+
+    #[test]
+    fn open_errors_are_returned_by_spawn() {
+        let result = ReaderHandle::spawn(|| Err(Error::DeviceNotFound));
+        assert!(matches!(result, Err(Error::DeviceNotFound)));
+    }
+}
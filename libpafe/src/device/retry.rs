@@ -0,0 +1,213 @@
+// libpafe-rs/libpafe/src/device/retry.rs
+
+use std::time::Duration;
+
+use crate::protocol::Command;
+use crate::{Error, Result};
+
+/// How `Device::execute` retries a failed command exchange.
+///
+/// An exchange is retried while fewer than `attempts` have been made and
+/// the command and error pass the policy's predicate (by default
+/// [`retry_idempotent`]). The pause before retry `n` (1-based) is
+/// `backoff * 2^(n-1)`, capped at `max_backoff`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    retry_if: fn(&Command, &Error) -> bool,
+}
+
+/// Default retry predicate: transient errors ([`Error::is_transient`]) of
+/// idempotent commands ([`Command::is_idempotent`]).
+///
+/// Writes are not retried: a timeout may mean only the response was lost
+/// and the card already applied the write, so sending it again could
+/// apply it twice (e.g. to a cyclic or purse service).
+pub fn retry_idempotent(cmd: &Command, error: &Error) -> bool {
+    cmd.is_idempotent() && error.is_transient()
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(100),
+            retry_if: retry_idempotent,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Three attempts, 10 ms initial backoff, transient errors of
+    /// idempotent commands only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A single attempt: errors are returned as they occur.
+    pub fn none() -> Self {
+        Self::default().attempts(1)
+    }
+
+    /// Total number of attempts, including the first (at least 1).
+    pub fn attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Pause before the first retry; doubled for each further retry.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Upper bound for the pause between two attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Select which failed commands are retried, e.g.
+    /// `|_, e| e.is_transient()` to retry writes as well.
+    pub fn retry_if(mut self, retry_if: fn(&Command, &Error) -> bool) -> Self {
+        self.retry_if = retry_if;
+        self
+    }
+
+    /// Total number of attempts.
+    pub fn max_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Whether `error`, returned by attempt `attempt` (1-based) of `cmd`,
+    /// should be retried.
+    pub fn should_retry(&self, cmd: &Command, error: &Error, attempt: u32) -> bool {
+        attempt < self.attempts && (self.retry_if)(cmd, error)
+    }
+
+    /// Pause after failed attempt `attempt` (1-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Run `op`, an exchange of `cmd`, under this policy and return its
+    /// first success or the error that ended the retries.
+    pub fn run<T>(&self, cmd: &Command, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(e) if self.should_retry(cmd, &e, attempt) => {
+                    std::thread::sleep(self.delay(attempt));
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AccessMode, BlockData, BlockElement, Idm, ServiceCode};
+
+    fn read() -> Command {
+        Command::RequestResponse {
+            idm: Idm::from_bytes([1; 8]),
+        }
+    }
+
+    #[test]
+    fn run_retries_transient_errors_only() {
+        let policy = RetryPolicy::new().backoff(Duration::ZERO);
+
+        let mut calls = 0;
+        let r = policy.run(&read(), || {
+            calls += 1;
+            if calls < 3 {
+                Err(Error::Timeout)
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(r.unwrap(), 3);
+
+        let mut calls = 0;
+        let r: Result<()> = policy.run(&read(), || {
+            calls += 1;
+            Err(Error::FelicaStatus {
+                status1: 0xFF,
+                status2: 0xA2,
+            })
+        });
+        assert!(r.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn run_gives_up_after_attempts() {
+        let mut calls = 0;
+        let r: Result<()> =
+            RetryPolicy::new()
+                .attempts(2)
+                .backoff(Duration::ZERO)
+                .run(&read(), || {
+                    calls += 1;
+                    Err(Error::Timeout)
+                });
+        assert!(matches!(r, Err(Error::Timeout)));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn delay_doubles_up_to_cap() {
+        let policy = RetryPolicy::new()
+            .backoff(Duration::from_millis(10))
+            .max_backoff(Duration::from_millis(30));
+        assert_eq!(policy.delay(1), Duration::from_millis(10));
+        assert_eq!(policy.delay(2), Duration::from_millis(20));
+        assert_eq!(policy.delay(3), Duration::from_millis(30));
+    }
+
+    #[test]
+    fn custom_predicate_selects_retried_errors() {
+        let policy = RetryPolicy::new()
+            .backoff(Duration::ZERO)
+            .retry_if(|_, e| matches!(e, Error::PollingFailed));
+        let mut calls = 0;
+        let _: Result<()> = policy.run(&read(), || {
+            calls += 1;
+            Err(Error::Timeout)
+        });
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn writes_are_not_retried_by_default() {
+        let write = Command::WriteWithoutEncryption {
+            idm: Idm::from_bytes([1; 8]),
+            service: ServiceCode::new(0x0009),
+            block: BlockElement::new(0, AccessMode::Normal, 0),
+            data: BlockData::from_bytes([0; 16]),
+        };
+        let policy = RetryPolicy::new().backoff(Duration::ZERO);
+        let mut calls = 0;
+        let _: Result<()> = policy.run(&write, || {
+            calls += 1;
+            Err(Error::Timeout)
+        });
+        assert_eq!(calls, 1);
+
+        // Opting in retries them like any other command
+        let mut calls = 0;
+        let _: Result<()> = policy.retry_if(|_, e| e.is_transient()).run(&write, || {
+            calls += 1;
+            Err(Error::Timeout)
+        });
+        assert_eq!(calls, 3);
+    }
+}
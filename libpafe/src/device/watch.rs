@@ -0,0 +1,253 @@
+// libpafe-rs/libpafe/src/device/watch.rs
+
+use std::time::Duration;
+
+use crate::card::Card;
+use crate::device::{Device, Initialized};
+use crate::types::{CardType, Idm, SystemCode, Uid};
+use crate::{Error, Result};
+
+/// Card presence change reported by [`Watch`].
+#[derive(Debug, Clone)]
+pub enum CardEvent {
+    /// A card entered the field.
+    Arrived(Card),
+    /// The FeliCa card with this IDm left the field.
+    Departed(Idm),
+    /// The Type A/B card with this UID left the field.
+    DepartedUid(Uid),
+}
+
+/// Settings for [`Device::watch`].
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    pub(crate) poll_interval: Duration,
+    pub(crate) debounce: u32,
+    pub(crate) system_codes: Vec<SystemCode>,
+    pub(crate) card_types: Vec<CardType>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(250),
+            debounce: 2,
+            system_codes: vec![SystemCode::new(0xffff)],
+            card_types: vec![CardType::TypeF],
+        }
+    }
+}
+
+impl WatchConfig {
+    /// FeliCa wildcard polling every 250 ms with a debounce of 2.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause between two polling or presence-check rounds.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Consecutive failed presence checks before a departure is reported
+    /// (at least 1).
+    pub fn debounce(mut self, checks: u32) -> Self {
+        self.debounce = checks.max(1);
+        self
+    }
+
+    /// System codes polled in turn for FeliCa cards.
+    pub fn system_codes(mut self, codes: Vec<SystemCode>) -> Self {
+        self.system_codes = codes;
+        self
+    }
+
+    /// Card types looked for, in priority order. Type A/B need a device
+    /// model that supports them (S330).
+    pub fn card_types(mut self, types: Vec<CardType>) -> Self {
+        self.card_types = types;
+        self
+    }
+}
+
+/// Card currently in the field and its consecutive missed presence checks.
+pub(crate) struct Present {
+    pub(crate) card: Card,
+    pub(crate) misses: u32,
+}
+
+/// Iterator returned by [`Device::watch`]. It never ends on its own;
+/// errors that do not simply mean "no card" (USB failures etc.) are
+/// yielded and the caller decides whether to keep watching.
+pub struct Watch<'d> {
+    device: &'d mut Device<Initialized>,
+    config: WatchConfig,
+    present: Option<Present>,
+    started: bool,
+}
+
+impl<'d> Watch<'d> {
+    pub(crate) fn new(device: &'d mut Device<Initialized>, config: WatchConfig) -> Self {
+        Self {
+            device,
+            config,
+            present: None,
+            started: false,
+        }
+    }
+
+    /// Card currently considered present, if any.
+    pub fn current(&self) -> Option<&Card> {
+        self.present.as_ref().map(|p| &p.card)
+    }
+
+    /// Poll every configured card type and system code once.
+    fn detect(&mut self) -> Result<Option<Card>> {
+        for &card_type in &self.config.card_types {
+            if card_type == CardType::TypeF {
+                for &sc in &self.config.system_codes {
+                    match self.device.polling(sc) {
+                        Ok(card) => return Ok(Some(card)),
+                        Err(e) if means_no_card(&e) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            } else if let Some(card) = poll_target(self.device, card_type)? {
+                return Ok(Some(card));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether `card` still answers: RequestResponse for FeliCa, a fresh
+    /// poll with the same UID for Type A/B.
+    fn still_present(&mut self, card: &Card) -> Result<bool> {
+        let answer = match card.uid() {
+            None => card.request_response_mode(self.device).map(|_| true),
+            Some(uid) => poll_target(self.device, card.card_type())
+                .map(|found| found.is_some_and(|c| c.uid() == Some(uid))),
+        };
+        match answer {
+            Err(e) if means_no_card(&e) => Ok(false),
+            other => other,
+        }
+    }
+
+    fn step(&mut self) -> Result<Option<CardEvent>> {
+        match self.present.take() {
+            Some(mut present) => {
+                if self.still_present(&present.card)? {
+                    present.misses = 0;
+                } else {
+                    present.misses += 1;
+                    if present.misses >= self.config.debounce {
+                        return Ok(Some(departure(&present.card)));
+                    }
+                }
+                self.present = Some(present);
+                Ok(None)
+            }
+            None => {
+                let found = self.detect()?;
+                Ok(found.map(|card| {
+                    self.present = Some(Present {
+                        card: card.clone(),
+                        misses: 0,
+                    });
+                    CardEvent::Arrived(card)
+                }))
+            }
+        }
+    }
+}
+
+impl Iterator for Watch<'_> {
+    type Item = Result<CardEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.started {
+                std::thread::sleep(self.config.poll_interval);
+            }
+            self.started = true;
+            match self.step() {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Look for one Type A/B target.
+fn poll_target(device: &mut Device<Initialized>, card_type: CardType) -> Result<Option<Card>> {
+    let sc = SystemCode::new(0xffff);
+    match device.list_passive_targets(card_type, sc, 1, crate::utils::DEFAULT_READ_TIMEOUT_MS) {
+        Ok(cards) => Ok(cards.into_iter().next()),
+        Err(e) if means_no_card(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn departure(card: &Card) -> CardEvent {
+    match (card.idm(), card.uid()) {
+        (Some(idm), _) => CardEvent::Departed(*idm),
+        (None, Some(uid)) => CardEvent::DepartedUid(uid.clone()),
+        (None, None) => unreachable!("every card has an IDm or a UID"),
+    }
+}
+
+/// Errors that only mean no card (or a different card) answered.
+pub(crate) fn means_no_card(e: &Error) -> bool {
+    e.is_transient() || matches!(e, Error::PollingFailed | Error::IdmMismatch { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TEST_IDM, mock_device, polling_frame, response_mode_frame};
+
+    fn garbage() -> Vec<u8> {
+        vec![0xde, 0xad]
+    }
+
+    fn fast() -> WatchConfig {
+        WatchConfig::new().poll_interval(Duration::ZERO)
+    }
+
+    #[test]
+    fn watch_reports_arrival_then_departure_after_debounce() {
+        let mut dev = mock_device(vec![
+            garbage(),                     // no card yet
+            polling_frame(TEST_IDM),       // arrival
+            response_mode_frame(TEST_IDM), // still present
+            garbage(),                     // first miss
+            response_mode_frame(TEST_IDM), // back: misses reset
+            garbage(),
+            garbage(), // second consecutive miss: departure
+        ]);
+        let mut watch = dev.watch(fast().debounce(2));
+
+        match watch.next() {
+            Some(Ok(CardEvent::Arrived(card))) => {
+                assert_eq!(card.idm(), Some(&Idm::from_bytes(TEST_IDM)))
+            }
+            other => panic!("expected arrival, got {:?}", other),
+        }
+        assert!(watch.current().is_some());
+        match watch.next() {
+            Some(Ok(CardEvent::Departed(idm))) => assert_eq!(idm, Idm::from_bytes(TEST_IDM)),
+            other => panic!("expected departure, got {:?}", other),
+        }
+        assert!(watch.current().is_none());
+    }
+
+    #[test]
+    fn watch_polls_each_system_code() {
+        let mut dev = mock_device(vec![garbage(), polling_frame(TEST_IDM)]);
+        let config = fast().system_codes(vec![SystemCode::new(0x8008), SystemCode::new(0x0003)]);
+        let mut watch = dev.watch(config);
+        assert!(matches!(watch.next(), Some(Ok(CardEvent::Arrived(_)))));
+    }
+}
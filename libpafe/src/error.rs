@@ -1,5 +1,6 @@
 // libpafe-rs/libpafe/src/error.rs
 
+use crate::protocol::status::{StatusFlag, failing_element};
 use thiserror::Error;
 
 /// 共通エラー型
@@ -20,20 +21,46 @@ pub enum Error {
     #[error("invalid packet length: expected {expected}, got {actual}")]
     InvalidLength { expected: usize, actual: usize },
 
-    #[error("felica error: status=({status1:#04x}, {status2:#04x})")]
+    #[error(
+        "felica error: status=({status1:#04x}, {status2:#04x}): {}",
+        StatusFlag::from(*.status2)
+    )]
     FelicaStatus { status1: u8, status2: u8 },
-    #[error("felica error at block {index}: status=({status1:#04x}, {status2:#04x})")]
+    #[error(
+        "felica error at block {index}: status=({status1:#04x}, {status2:#04x}): {}",
+        StatusFlag::from(*.status2)
+    )]
     FelicaBlockStatus {
         index: usize,
         status1: u8,
         status2: u8,
     },
 
+    /// A request split into several commands failed at `chunk`, which
+    /// covered elements `start..end` of the original request. Earlier
+    /// chunks completed.
+    #[error("chunk {chunk} (elements {start}..{end}) failed: {source}")]
+    ChunkFailed {
+        /// Index of the failing command
+        chunk: usize,
+        /// First request element the command covered
+        start: usize,
+        /// One past the last request element the command covered
+        end: usize,
+        /// Error of the failing command
+        source: Box<Error>,
+    },
+
     #[error("checksum mismatch: expected {expected:#04x}, got {actual:#04x}")]
     ChecksumMismatch { expected: u8, actual: u8 },
     #[error("frame format error: {0}")]
     FrameFormat(String),
 
+    /// The reader chip answered with an error frame
+    /// (`00 00 FF 01 FF 7F 81 00`), rejecting the command it was sent.
+    #[error("device rejected the command with an error frame")]
+    ErrorFrame,
+
     #[error("unexpected response code: expected {expected:#04x}, got {actual:#04x}")]
     UnexpectedResponse { expected: u8, actual: u8 },
 
@@ -43,8 +70,110 @@ pub enum Error {
     #[error("operation timed out")]
     Timeout,
 
+    /// A blocking wait was stopped through its `CancelToken`.
+    #[error("operation cancelled")]
+    Cancelled,
+
     #[error("unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    /// A command violates a FeliCa spec limit and was rejected before
+    /// being sent.
+    #[error("invalid command: {0}")]
+    InvalidCommand(String),
+
+    /// The on-card write journal is misconfigured or holds an
+    /// unreadable header.
+    #[error("journal error: {0}")]
+    Journal(String),
+
+    /// The thread owning the device behind a `ReaderHandle` has stopped.
+    #[error("reader thread has stopped")]
+    ReaderClosed,
+
+    /// A request run by a `ReaderHandle` panicked. The reader thread
+    /// keeps serving other requests.
+    #[error("reader request panicked")]
+    RequestPanicked,
+
+    /// A response came from a different card than the one addressed.
+    #[error("IDm mismatch: expected {}, got {}", expected.to_hex(), actual.to_hex())]
+    IdmMismatch {
+        /// IDm of the addressed card
+        expected: crate::types::Idm,
+        /// IDm carried by the response
+        actual: crate::types::Idm,
+    },
+}
+
+impl Error {
+    /// Error for a non-zero FeliCa status pair. When status flag 1 points
+    /// at a single block list element the error is `FelicaBlockStatus`
+    /// carrying that element's index, otherwise `FelicaStatus`.
+    pub fn from_status(status1: u8, status2: u8) -> Self {
+        match failing_element(status1) {
+            Some(index) => Error::FelicaBlockStatus {
+                index,
+                status1,
+                status2,
+            },
+            None => Error::FelicaStatus { status1, status2 },
+        }
+    }
+
+    /// Whether the error may go away when the exchange is repeated: lost
+    /// or garbled frames and recoverable USB conditions. Card status
+    /// errors are deterministic and not transient.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Timeout
+            | Error::ChecksumMismatch { .. }
+            | Error::FrameFormat(_)
+            | Error::InvalidLength { .. }
+            | Error::UnexpectedResponse { .. } => true,
+            #[cfg(feature = "usb")]
+            Error::Usb(e) => matches!(
+                e,
+                rusb::Error::Timeout
+                    | rusb::Error::Pipe
+                    | rusb::Error::Io
+                    | rusb::Error::Busy
+                    | rusb::Error::Interrupted
+                    | rusb::Error::Overflow
+            ),
+            Error::ChunkFailed { source, .. } => source.is_transient(),
+            _ => false,
+        }
+    }
+
+    /// Whether the reader is gone or unusable, so neither retrying nor
+    /// talking to another card can succeed.
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Error::DeviceNotFound | Error::ReaderClosed => true,
+            #[cfg(feature = "usb")]
+            Error::Usb(e) => matches!(
+                e,
+                rusb::Error::NoDevice
+                    | rusb::Error::NotFound
+                    | rusb::Error::Access
+                    | rusb::Error::NotSupported
+            ),
+            Error::ChunkFailed { source, .. } => source.is_fatal(),
+            _ => false,
+        }
+    }
+
+    /// Decoded status flag 2 of a FeliCa status error
+    pub fn status_flag(&self) -> Option<StatusFlag> {
+        match self {
+            Error::FelicaStatus { status2, .. } | Error::FelicaBlockStatus { status2, .. } => {
+                Some(StatusFlag::from(*status2))
+            }
+            Error::ChunkFailed { source, .. } => source.status_flag(),
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -53,6 +182,26 @@ pub type Result<T> = std::result::Result<T, Error>;
 mod tests {
     use super::*;
 
+    #[test]
+    fn transient_and_fatal_classification() {
+        assert!(Error::Timeout.is_transient());
+        assert!(Error::FrameFormat("x".into()).is_transient());
+        assert!(!Error::Timeout.is_fatal());
+        assert!(Error::DeviceNotFound.is_fatal());
+        assert!(!Error::DeviceNotFound.is_transient());
+
+        let status = Error::from_status(0xFF, 0xA6);
+        assert!(!status.is_transient() && !status.is_fatal());
+
+        let chunk = Error::ChunkFailed {
+            chunk: 1,
+            start: 11,
+            end: 15,
+            source: Box::new(Error::Timeout),
+        };
+        assert!(chunk.is_transient());
+    }
+
     #[test]
     fn invalid_length_display() {
         let err = Error::InvalidLength {
@@ -74,6 +223,29 @@ mod tests {
         assert!(s.contains("felica error"));
     }
 
+    #[test]
+    fn from_status_reports_failing_element() {
+        let err = Error::from_status(0x02, 0xA8);
+        match err {
+            Error::FelicaBlockStatus {
+                index: 1,
+                status1: 0x02,
+                status2: 0xA8,
+            } => {}
+            ref other => panic!("expected FelicaBlockStatus, got {:?}", other),
+        }
+        assert_eq!(err.status_flag(), Some(StatusFlag::IllegalBlockNumber));
+        assert!(format!("{}", err).contains("illegal block number"));
+
+        match Error::from_status(0xFF, 0xA1) {
+            Error::FelicaStatus {
+                status1: 0xFF,
+                status2: 0xA1,
+            } => {}
+            other => panic!("expected FelicaStatus, got {:?}", other),
+        }
+    }
+
     #[test]
     fn unexpected_response_display() {
         let err = Error::UnexpectedResponse {
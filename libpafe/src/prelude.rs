@@ -2,13 +2,20 @@
 
 pub use crate::card::Card;
 pub use crate::card::CardInfo;
+pub use crate::card::CardSession;
+#[cfg(feature = "async")]
+pub use crate::device::AsyncDevice;
 pub use crate::device::Device;
+pub use crate::device::{CardEvent, ReaderHandle, RetryPolicy, WatchConfig};
 pub use crate::device::{Initialized, Uninitialized};
-pub use crate::protocol::{Command, Response};
+pub use crate::protocol::{Command, Response, StatusFlag};
 pub use crate::{
-    AccessMode, Atqb, BlockData, BlockElement, CardType, DeviceType, Error, Idm, Pmm, Result,
-    ServiceCode, SystemCode, Uid,
+    AccessMode, Atqb, BlockData, BlockElement, CardType, ChipKind, DeviceType, Error, Idm, Pmm,
+    PollingRequest, PollingRequestData, Result, ServiceAccess, ServiceCode, ServiceType,
+    SystemCode, TimeSlots, Uid,
 };
 
 // Re-export small utilities for convenience
-pub use crate::utils::{bytes_to_hex, bytes_to_hex_spaced, default_read_timeout, ms, parse_hex};
+pub use crate::utils::{
+    CancelToken, bytes_to_hex, bytes_to_hex_spaced, default_read_timeout, ms, parse_hex,
+};
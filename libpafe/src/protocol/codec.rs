@@ -8,7 +8,7 @@ use super::responses::Response;
 
 /// Encode a Command into a full wire frame (with preamble/LCS/DCS/postamble).
 pub fn encode_command_frame(cmd: &Command) -> Result<Vec<u8>> {
-    let payload = cmd.encode();
+    let payload = cmd.encode()?;
     Frame::encode(&payload)
 }
 
@@ -40,11 +40,14 @@ mod tests {
             Response::Polling {
                 idm,
                 pmm,
-                system_code,
+                request_data,
             } => {
                 assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
                 assert_eq!(pmm.as_bytes(), &[9, 10, 11, 12, 13, 14, 15, 16]);
-                assert_eq!(system_code.as_u16(), 0x0a0b);
+                assert_eq!(
+                    request_data.map(SystemCode::from_le_bytes),
+                    Some(SystemCode::new(0x0a0b))
+                );
             }
             other => panic!("unexpected response: {:?}", other),
         }
@@ -94,7 +97,7 @@ mod tests {
     // unexpected payloads, but must not panic.
     proptest! {
         #[test]
-        fn codec_decode_frame_no_panic(cmd in prop::sample::select(vec![0x00u8,0x06,0x02,0x04,0x0c,0x0a]),
+        fn codec_decode_frame_no_panic(cmd in prop::sample::select(vec![0x00u8,0x06,0x02,0x04,0x0c,0x0a,0x0e,0x1e]),
                                         payload in prop::collection::vec(any::<u8>(), 0..64)) {
             use std::panic::{catch_unwind, AssertUnwindSafe};
             let frame = Frame::encode(&payload).unwrap();
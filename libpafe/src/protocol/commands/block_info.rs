@@ -0,0 +1,58 @@
+// libpafe-rs/libpafe/src/protocol/commands/block_info.rs
+
+use crate::types::Idm;
+
+/// Encode RequestBlockInformation command (FeliCa command code 0x0E)
+/// Layout: command_code(1) + idm(8) + node_count(1) + node_code_list(2*N)
+pub(crate) fn encode_request_block_information(idm: Idm, node_codes: &[u16]) -> Vec<u8> {
+    encode_node_list(0x0E, idm, node_codes)
+}
+
+/// Encode RequestBlockInformationEx command (FeliCa command code 0x1E)
+/// Layout: command_code(1) + idm(8) + node_count(1) + node_code_list(2*N)
+pub(crate) fn encode_request_block_information_ex(idm: Idm, node_codes: &[u16]) -> Vec<u8> {
+    encode_node_list(0x1E, idm, node_codes)
+}
+
+/// Node counts are checked by [`Command::encode`] before this runs.
+///
+/// [`Command::encode`]: super::Command::encode
+fn encode_node_list(code: u8, idm: Idm, node_codes: &[u16]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(1 + 8 + 1 + node_codes.len() * 2);
+    buf.push(code);
+    buf.extend_from_slice(idm.as_bytes());
+    buf.push(node_codes.len() as u8);
+    for n in node_codes {
+        buf.extend_from_slice(&n.to_le_bytes());
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Idm;
+
+    #[test]
+    fn encode_request_block_information_basic() {
+        let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+        let p = encode_request_block_information(idm, &[0x090f]);
+        let mut expected = vec![0x0E];
+        expected.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        expected.push(1);
+        expected.extend_from_slice(&0x090fu16.to_le_bytes());
+        assert_eq!(p, expected);
+    }
+
+    #[test]
+    fn encode_request_block_information_ex_basic() {
+        let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+        let p = encode_request_block_information_ex(idm, &[0x090f, 0x1008]);
+        let mut expected = vec![0x1E];
+        expected.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        expected.push(2);
+        expected.extend_from_slice(&0x090fu16.to_le_bytes());
+        expected.extend_from_slice(&0x1008u16.to_le_bytes());
+        assert_eq!(p, expected);
+    }
+}
@@ -1,5 +1,6 @@
 // libpafe-rs/libpafe/src/protocol/commands/mod.rs
 
+pub mod block_info;
 pub mod polling;
 pub mod read;
 pub mod search;
@@ -7,12 +8,17 @@ pub mod service;
 pub mod system;
 pub mod write;
 
+use block_info::{encode_request_block_information, encode_request_block_information_ex};
 pub use polling::encode_polling;
-pub use read::encode_read;
+use read::encode_read;
 pub use search::encode_search_service_code;
-pub use service::{encode_request_response, encode_request_service};
+pub use service::encode_request_response;
+use service::encode_request_service;
 pub use system::encode_request_system_code;
-pub use write::{encode_write, encode_write_multi};
+use write::{encode_write, encode_write_multi};
+
+use crate::constants::{FELICA_MAX_NODES, FELICA_MAX_PAYLOAD_LEN, FELICA_MAX_SERVICES};
+use crate::{Error, Result};
 
 /// High-level Command enum. New commands should be added here and
 /// their per-command encoder placed in `protocol::commands::<name>.rs`.
@@ -55,6 +61,16 @@ pub enum Command {
         idm: crate::types::Idm,
         index: u16,
     },
+    /// Number of blocks assigned to each service (or free in each area)
+    RequestBlockInformation {
+        idm: crate::types::Idm,
+        node_codes: Vec<u16>,
+    },
+    /// Assigned and free block counts for each node
+    RequestBlockInformationEx {
+        idm: crate::types::Idm,
+        node_codes: Vec<u16>,
+    },
 }
 
 impl Command {
@@ -69,11 +85,122 @@ impl Command {
             Self::RequestResponse { .. } => 0x04,
             Self::RequestSystemCode { .. } => 0x0c,
             Self::SearchServiceCode { .. } => 0x0a,
+            Self::RequestBlockInformation { .. } => 0x0e,
+            Self::RequestBlockInformationEx { .. } => 0x1e,
         }
     }
 
+    /// IDm of the addressed card, or `None` for Polling which addresses
+    /// every card in the field.
+    pub fn idm(&self) -> Option<crate::types::Idm> {
+        match self {
+            Self::Polling { .. } => None,
+            Self::ReadWithoutEncryption { idm, .. }
+            | Self::WriteWithoutEncryption { idm, .. }
+            | Self::WriteWithoutEncryptionMulti { idm, .. }
+            | Self::RequestService { idm, .. }
+            | Self::RequestResponse { idm }
+            | Self::RequestSystemCode { idm }
+            | Self::SearchServiceCode { idm, .. }
+            | Self::RequestBlockInformation { idm, .. }
+            | Self::RequestBlockInformationEx { idm, .. } => Some(*idm),
+        }
+    }
+
+    /// Whether sending the command twice has the same effect as sending it
+    /// once. Reads, Polling and the Request*/Search queries are; writes
+    /// are not, since a write whose response was lost may already have
+    /// been applied by the card.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(
+            self,
+            Self::WriteWithoutEncryption { .. } | Self::WriteWithoutEncryptionMulti { .. }
+        )
+    }
+
     /// Encode the command into the raw payload (command code + params).
-    pub fn encode(&self) -> Vec<u8> {
+    ///
+    /// The command is checked with [`Command::validate`] first, and the
+    /// encoded payload must fit in a single frame; otherwise
+    /// `Error::InvalidCommand` is returned and nothing should be sent.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let payload = self.encode_unchecked();
+        if payload.len() > FELICA_MAX_PAYLOAD_LEN {
+            return Err(Error::InvalidCommand(format!(
+                "encoded payload is {} bytes, frame limit is {}",
+                payload.len(),
+                FELICA_MAX_PAYLOAD_LEN
+            )));
+        }
+        Ok(payload)
+    }
+
+    /// Check the command against the FeliCa spec limits: service and node
+    /// list sizes, block list element service indexes, block/data count
+    /// agreement and the polling parameters.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Polling {
+                request_code,
+                time_slot,
+                ..
+            } => {
+                if *request_code > 0x02 {
+                    return Err(Error::InvalidCommand(format!(
+                        "unknown polling request code {:#04x}",
+                        request_code
+                    )));
+                }
+                if !matches!(time_slot, 0x00 | 0x01 | 0x03 | 0x07 | 0x0f) {
+                    return Err(Error::InvalidCommand(format!(
+                        "invalid polling time slot {:#04x}",
+                        time_slot
+                    )));
+                }
+                Ok(())
+            }
+            Self::ReadWithoutEncryption {
+                services, blocks, ..
+            } => validate_block_list(services, blocks),
+            Self::WriteWithoutEncryption { block, .. } => {
+                validate_block_list(&[crate::types::ServiceCode::new(0)], &[*block])
+            }
+            Self::WriteWithoutEncryptionMulti {
+                services,
+                blocks,
+                data,
+                ..
+            } => {
+                validate_block_list(services, blocks)?;
+                if data.len() != blocks.len() {
+                    return Err(Error::InvalidCommand(format!(
+                        "{} data blocks for {} block list elements",
+                        data.len(),
+                        blocks.len()
+                    )));
+                }
+                Ok(())
+            }
+            Self::RequestService { node_codes, .. }
+            | Self::RequestBlockInformation { node_codes, .. }
+            | Self::RequestBlockInformationEx { node_codes, .. } => {
+                if node_codes.is_empty() || node_codes.len() > FELICA_MAX_NODES {
+                    return Err(Error::InvalidCommand(format!(
+                        "{} node codes, expected 1..={}",
+                        node_codes.len(),
+                        FELICA_MAX_NODES
+                    )));
+                }
+                Ok(())
+            }
+            Self::RequestResponse { .. }
+            | Self::RequestSystemCode { .. }
+            | Self::SearchServiceCode { .. } => Ok(()),
+        }
+    }
+
+    fn encode_unchecked(&self) -> Vec<u8> {
         match self {
             Self::Polling {
                 system_code,
@@ -103,14 +230,54 @@ impl Command {
             Self::RequestResponse { idm } => encode_request_response(*idm),
             Self::RequestSystemCode { idm } => encode_request_system_code(*idm),
             Self::SearchServiceCode { idm, index } => encode_search_service_code(*idm, *index),
+            Self::RequestBlockInformation { idm, node_codes } => {
+                encode_request_block_information(*idm, &node_codes[..])
+            }
+            Self::RequestBlockInformationEx { idm, node_codes } => {
+                encode_request_block_information_ex(*idm, &node_codes[..])
+            }
         }
     }
 }
 
+/// Check a service list and the block list elements that refer to it.
+fn validate_block_list(
+    services: &[crate::types::ServiceCode],
+    blocks: &[crate::types::BlockElement],
+) -> Result<()> {
+    if services.is_empty() || services.len() > FELICA_MAX_SERVICES {
+        return Err(Error::InvalidCommand(format!(
+            "{} services, expected 1..={}",
+            services.len(),
+            FELICA_MAX_SERVICES
+        )));
+    }
+    if blocks.is_empty() || blocks.len() > u8::MAX as usize {
+        return Err(Error::InvalidCommand(format!(
+            "{} block list elements, expected 1..={}",
+            blocks.len(),
+            u8::MAX
+        )));
+    }
+    if let Some((i, b)) = blocks
+        .iter()
+        .enumerate()
+        .find(|(_, b)| b.service_index as usize >= services.len())
+    {
+        return Err(Error::InvalidCommand(format!(
+            "block list element {} refers to service index {} of {}",
+            i,
+            b.service_index,
+            services.len()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::SystemCode;
+    use crate::types::{AccessMode, BlockData, BlockElement, Idm, ServiceCode, SystemCode};
 
     #[test]
     fn command_encode_polling() {
@@ -121,6 +288,76 @@ mod tests {
         };
 
         assert_eq!(cmd.command_code(), 0x00);
-        assert_eq!(cmd.encode(), vec![0x00, 0x34, 0x12, 1, 0]);
+        assert_eq!(cmd.encode().unwrap(), vec![0x00, 0x34, 0x12, 1, 0]);
+    }
+
+    fn read_cmd(services: usize, blocks: Vec<BlockElement>) -> Command {
+        Command::ReadWithoutEncryption {
+            idm: Idm::from_bytes([0; 8]),
+            services: (0..services)
+                .map(|i| ServiceCode::new(0x000b | ((i as u16) << 6)))
+                .collect(),
+            blocks,
+        }
+    }
+
+    fn assert_invalid(cmd: Command) {
+        match cmd.encode() {
+            Err(Error::InvalidCommand(_)) => {}
+            other => panic!("expected InvalidCommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_too_many_services() {
+        let blk = BlockElement::new(0, AccessMode::Normal, 0);
+        assert!(read_cmd(16, vec![blk]).encode().is_ok());
+        assert_invalid(read_cmd(17, vec![blk]));
+        assert_invalid(read_cmd(0, vec![blk]));
+    }
+
+    #[test]
+    fn rejects_dangling_service_index() {
+        let blk = BlockElement::new(2, AccessMode::Normal, 0);
+        assert_invalid(read_cmd(2, vec![blk]));
+        assert_invalid(read_cmd(1, vec![]));
+    }
+
+    #[test]
+    fn rejects_write_data_count_mismatch() {
+        let blk = BlockElement::new(0, AccessMode::Normal, 0);
+        assert_invalid(Command::WriteWithoutEncryptionMulti {
+            idm: Idm::from_bytes([0; 8]),
+            services: vec![ServiceCode::new(0x0009)],
+            blocks: vec![blk, blk],
+            data: vec![BlockData::from_bytes([0; 16])],
+        });
+    }
+
+    #[test]
+    fn rejects_oversized_payload() {
+        // 16 blocks of 16 bytes plus headers exceed the 255-byte frame
+        let blocks: Vec<_> = (0..16)
+            .map(|n| BlockElement::new(0, AccessMode::Normal, n))
+            .collect();
+        assert_invalid(Command::WriteWithoutEncryptionMulti {
+            idm: Idm::from_bytes([0; 8]),
+            services: vec![ServiceCode::new(0x0009)],
+            blocks,
+            data: vec![BlockData::from_bytes([0; 16]); 16],
+        });
+    }
+
+    #[test]
+    fn rejects_node_and_polling_limits() {
+        assert_invalid(Command::RequestService {
+            idm: Idm::from_bytes([0; 8]),
+            node_codes: vec![0x0000; 33],
+        });
+        assert_invalid(Command::Polling {
+            system_code: SystemCode::new(0xffff),
+            request_code: 0,
+            time_slot: 2,
+        });
     }
 }
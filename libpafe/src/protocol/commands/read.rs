@@ -3,7 +3,18 @@
 use crate::types::{BlockElement, Idm, ServiceCode};
 
 /// Encode ReadWithoutEncryption command payload (FeliCa command code 0x06)
-pub fn encode_read(idm: Idm, services: &[ServiceCode], blocks: &[BlockElement]) -> Vec<u8> {
+/// Layout: command_code(1) + idm(8) + service_count(1) + service_code_list(2*N)
+///         + block_count(1) + block_list(2 or 3 bytes per element)
+///
+/// Each block element picks the 2-byte or 3-byte form from its block
+/// number (see `BlockElement::encode`).
+///
+/// Lengths are not checked here: build a [`Command`] and call
+/// [`Command::encode`], which validates them first.
+///
+/// [`Command`]: super::Command
+/// [`Command::encode`]: super::Command::encode
+pub(crate) fn encode_read(idm: Idm, services: &[ServiceCode], blocks: &[BlockElement]) -> Vec<u8> {
     let mut buf = Vec::new();
     buf.push(0x06); // ReadWithoutEncryption command code
     buf.extend_from_slice(idm.as_bytes());
@@ -30,7 +41,7 @@ mod tests {
     fn encode_read_basic() {
         let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
         let services = [ServiceCode::new(0x090f)];
-        let blocks = [BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0012)];
+        let blocks = [BlockElement::new(0, AccessMode::Normal, 0x0012)];
 
         let p = encode_read(idm, &services, &blocks);
         // manually build expected
@@ -39,8 +50,22 @@ mod tests {
         expected.push(1);
         expected.extend_from_slice(&ServiceCode::new(0x090f).to_le_bytes());
         expected.push(1);
-        expected.extend_from_slice(&[0, 2, 0x12]);
+        expected.extend_from_slice(&[0x80, 0x12]);
 
         assert_eq!(p, expected);
     }
+
+    #[test]
+    fn encode_read_mixed_element_forms() {
+        let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+        let services = [ServiceCode::new(0x090f)];
+        let blocks = [
+            BlockElement::new(0, AccessMode::Normal, 0x00ff),
+            BlockElement::new(0, AccessMode::Normal, 0x0123),
+        ];
+
+        let p = encode_read(idm, &services, &blocks);
+        // block_count followed by a 2-byte and a 3-byte element
+        assert_eq!(&p[12..], &[2, 0x80, 0xff, 0x00, 0x23, 0x01]);
+    }
 }
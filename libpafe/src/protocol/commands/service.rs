@@ -4,7 +4,13 @@ use crate::types::Idm;
 
 /// Encode RequestService command (FeliCa command code 0x02)
 /// Layout: command_code(1) + idm(8) + node_count(1) + node_code_list(2*N)
-pub fn encode_request_service(idm: Idm, node_codes: &[u16]) -> Vec<u8> {
+///
+/// Lengths are not checked here: build a [`Command`] and call
+/// [`Command::encode`], which validates them first.
+///
+/// [`Command`]: super::Command
+/// [`Command::encode`]: super::Command::encode
+pub(crate) fn encode_request_service(idm: Idm, node_codes: &[u16]) -> Vec<u8> {
     let mut buf = Vec::new();
     buf.push(0x02);
     buf.extend_from_slice(idm.as_bytes());
@@ -4,8 +4,8 @@ use crate::types::{BlockData, BlockElement, Idm, ServiceCode};
 
 /// Encode WriteWithoutEncryption command payload (FeliCa command code 0x08)
 /// Layout (single-block variant):
-/// command_code(1) + idm(8) + number_of_services(1) + service_code_list(2*N) + number_of_blocks(1) + block_list(3*N) + block_data(16*N)
-pub fn encode_write(
+/// command_code(1) + idm(8) + number_of_services(1) + service_code_list(2*N) + number_of_blocks(1) + block_list(2 or 3 bytes each) + block_data(16*N)
+pub(crate) fn encode_write(
     idm: Idm,
     service: ServiceCode,
     block: BlockElement,
@@ -21,8 +21,17 @@ fn block_data_to_bytes(b: &BlockData) -> [u8; 16] {
 
 /// Encode multi-block WriteWithoutEncryption command payload
 /// Layout: command_code(1) + idm(8) + service_count(1) + service_code_list(2*N)
-///         + block_count(1) + block_list(3*M) + block_data(16*M)
-pub fn encode_write_multi(
+///         + block_count(1) + block_list(2 or 3 bytes each) + block_data(16*M)
+///
+/// Block elements with a block number above 255 are emitted in the
+/// 3-byte form automatically.
+///
+/// Lengths are not checked here: build a [`Command`] and call
+/// [`Command::encode`], which validates them first.
+///
+/// [`Command`]: super::Command
+/// [`Command::encode`]: super::Command::encode
+pub(crate) fn encode_write_multi(
     idm: Idm,
     services: &[ServiceCode],
     blocks: &[BlockElement],
@@ -61,7 +70,7 @@ mod tests {
     fn encode_write_single_block() {
         let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
         let svc = ServiceCode::new(0x090f);
-        let blk = BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0012);
+        let blk = BlockElement::new(0, AccessMode::Normal, 0x0012);
         let data = BlockData::from_bytes([0x5A; 16]);
 
         let p = encode_write(idm, svc, blk, data);
@@ -81,8 +90,8 @@ mod tests {
     fn encode_write_multi_block() {
         let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
         let svc = ServiceCode::new(0x090f);
-        let blk1 = BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0012);
-        let blk2 = BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0013);
+        let blk1 = BlockElement::new(0, AccessMode::Normal, 0x0012);
+        let blk2 = BlockElement::new(0, AccessMode::Normal, 0x0013);
         let d1 = BlockData::from_bytes([0xAA; 16]);
         let d2 = BlockData::from_bytes([0xBB; 16]);
 
@@ -93,4 +102,19 @@ mod tests {
         assert!(p.windows(16).any(|w| w == [0xAA; 16]));
         assert!(p.windows(16).any(|w| w == [0xBB; 16]));
     }
+
+    #[test]
+    fn encode_write_multi_three_byte_element() {
+        let idm = Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+        let svc = ServiceCode::new(0x090f);
+        let blk = BlockElement::new(0, AccessMode::Normal, 0x0200);
+        let data = BlockData::from_bytes([0xCC; 16]);
+
+        let p = encode_write_multi(idm, &[svc], &[blk], &[data]);
+
+        // header(9) + service list(3) + block_count(1) + element(3) + data(16)
+        assert_eq!(p.len(), 9 + 3 + 1 + 3 + 16);
+        assert_eq!(&p[12..16], &[1, 0x00, 0x00, 0x02]);
+        assert_eq!(&p[16..], &[0xCC; 16]);
+    }
 }
@@ -7,32 +7,73 @@ use crate::{Error, Result};
 /// Format: [Preamble(3)] [Len(1)] [LCS(1)] [Payload(n)] [DCS(1)] [Postamble(1)]
 /// Preamble: 0x00 0x00 0xFF
 /// Postamble: 0x00
+///
+/// Payloads longer than 255 bytes use the PN53x extended form:
+/// [Preamble(3)] [0xFF 0xFF] [LenM(1)] [LenL(1)] [LCS(1)] [Payload(n)] [DCS(1)] [Postamble(1)]
+/// where LCS covers both length bytes.
 pub struct Frame {
     pub payload: Vec<u8>,
 }
 
+/// Link-layer role of a frame received from a PN53x-style chip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    /// `00 00 FF 00 FF 00`: the command was received, its response follows.
+    Ack,
+    /// `00 00 FF FF 00 00`: retransmission request.
+    Nack,
+    /// `00 00 FF 01 FF 7F 81 00`: the chip rejected the command.
+    Error,
+    /// Anything else, normally a frame carrying a payload.
+    Data,
+}
+
 impl Frame {
-    /// Encode a payload into a full FeliCa frame
+    /// Classify a received frame by its link-layer role. Only exact
+    /// ACK/NACK/error frames are recognised; everything else is `Data`.
+    pub fn kind(frame: &[u8]) -> FrameKind {
+        if frame == crate::constants::LINK_ACK_FRAME {
+            FrameKind::Ack
+        } else if frame == crate::constants::LINK_NACK_FRAME {
+            FrameKind::Nack
+        } else if frame == crate::constants::LINK_ERROR_FRAME {
+            FrameKind::Error
+        } else {
+            FrameKind::Data
+        }
+    }
+
+    /// Encode a payload into a full FeliCa frame, using the extended form
+    /// when the payload does not fit a one-byte length.
     pub fn encode(payload: &[u8]) -> Result<Vec<u8>> {
-        if payload.len() > 255 {
+        if payload.len() > crate::constants::EXTENDED_MAX_PAYLOAD_LEN {
             return Err(Error::InvalidLength {
-                expected: 255,
+                expected: crate::constants::EXTENDED_MAX_PAYLOAD_LEN,
                 actual: payload.len(),
             });
         }
 
-        let len = payload.len() as u8;
-        let mut out = Vec::with_capacity(3 + 1 + 1 + payload.len() + 1 + 1);
+        let mut out = Vec::with_capacity(crate::constants::EXTENDED_MIN_FRAME_LEN + payload.len());
         out.extend_from_slice(&crate::constants::FELICA_PREAMBLE);
-        out.push(len);
-        out.push(lcs(len));
+        if payload.len() > crate::constants::FELICA_MAX_PAYLOAD_LEN {
+            let [len_m, len_l] = (payload.len() as u16).to_be_bytes();
+            out.extend_from_slice(&crate::constants::EXTENDED_FRAME_MARKER);
+            out.push(len_m);
+            out.push(len_l);
+            out.push(lcs(len_m.wrapping_add(len_l)));
+        } else {
+            let len = payload.len() as u8;
+            out.push(len);
+            out.push(lcs(len));
+        }
         out.extend_from_slice(payload);
         out.push(dcs(payload));
         out.push(crate::constants::FELICA_POSTAMBLE);
         Ok(out)
     }
 
-    /// Decode a full FeliCa frame and return the payload
+    /// Decode a full FeliCa frame (normal or extended) and return the
+    /// payload
     pub fn decode(frame: &[u8]) -> Result<Vec<u8>> {
         // Minimal frame length: preamble(3) + len(1) + lcs(1) + dcs(1) + postamble(1)
         if frame.len() < crate::constants::FELICA_MIN_FRAME_LEN {
@@ -49,27 +90,16 @@ impl Frame {
             return Err(Error::FrameFormat("invalid preamble".into()));
         }
 
-        let len = frame[3];
-        let lcs_actual = frame[4];
-        let lcs_expected = lcs(len);
-        if lcs_actual != lcs_expected {
-            return Err(Error::ChecksumMismatch {
-                expected: lcs_expected,
-                actual: lcs_actual,
-            });
-        }
-
-        let required_len = 3 + 1 + 1 + (len as usize) + 1 + 1; // FELICA_MIN_FRAME_LEN + len
-        if frame.len() != required_len {
+        let header = Self::header(frame)?;
+        if frame.len() != header.frame_len() {
             return Err(Error::InvalidLength {
-                expected: required_len,
+                expected: header.frame_len(),
                 actual: frame.len(),
             });
         }
 
-        let payload_start = 5usize;
-        let payload_end = payload_start + (len as usize);
-        let payload = &frame[payload_start..payload_end];
+        let payload_end = header.payload_start + header.len;
+        let payload = &frame[header.payload_start..payload_end];
 
         let dcs_actual = frame[payload_end];
         let dcs_expected = dcs(payload);
@@ -86,6 +116,80 @@ impl Frame {
 
         Ok(payload.to_vec())
     }
+
+    /// Split a buffer holding back-to-back frames into candidate frames.
+    ///
+    /// Bytes that do not start with a preamble are skipped; a trailing
+    /// truncated frame is dropped. Candidates are not validated, so callers
+    /// still pass each one through [`Frame::decode`].
+    pub fn split(buf: &[u8]) -> Vec<&[u8]> {
+        let mut frames = Vec::new();
+        let mut pos = 0usize;
+        while pos + crate::constants::FELICA_MIN_FRAME_LEN <= buf.len() {
+            if buf[pos..pos + 3] != crate::constants::FELICA_PREAMBLE {
+                pos += 1;
+                continue;
+            }
+            let end = match Self::header(&buf[pos..]) {
+                Ok(header) => pos + header.frame_len(),
+                // Extended header cut short: treat it as a truncated tail
+                Err(Error::InvalidLength { .. }) => break,
+                // Length checksum mismatch: keep the normal-form length so
+                // decode reports the checksum
+                Err(_) => pos + crate::constants::FELICA_MIN_FRAME_LEN + buf[pos + 3] as usize,
+            };
+            if end > buf.len() {
+                break;
+            }
+            frames.push(&buf[pos..end]);
+            pos = end;
+        }
+        frames
+    }
+
+    /// Parse the length part of the frame starting at `frame[0]`, whose
+    /// preamble has been checked, and verify its LCS.
+    fn header(frame: &[u8]) -> Result<Header> {
+        let extended = frame[3..5] == crate::constants::EXTENDED_FRAME_MARKER;
+        let (len, lcs_actual, lcs_expected, payload_start) = if extended {
+            if frame.len() < crate::constants::EXTENDED_MIN_FRAME_LEN {
+                return Err(Error::InvalidLength {
+                    expected: crate::constants::EXTENDED_MIN_FRAME_LEN,
+                    actual: frame.len(),
+                });
+            }
+            let (len_m, len_l) = (frame[5], frame[6]);
+            (
+                u16::from_be_bytes([len_m, len_l]) as usize,
+                frame[7],
+                lcs(len_m.wrapping_add(len_l)),
+                8,
+            )
+        } else {
+            (frame[3] as usize, frame[4], lcs(frame[3]), 5)
+        };
+
+        if lcs_actual != lcs_expected {
+            return Err(Error::ChecksumMismatch {
+                expected: lcs_expected,
+                actual: lcs_actual,
+            });
+        }
+        Ok(Header { len, payload_start })
+    }
+}
+
+/// Payload length and offset of a decoded frame header.
+struct Header {
+    len: usize,
+    payload_start: usize,
+}
+
+impl Header {
+    /// Total frame length: header, payload, DCS and postamble.
+    fn frame_len(&self) -> usize {
+        self.payload_start + self.len + 2
+    }
 }
 
 #[cfg(test)]
@@ -109,6 +213,89 @@ mod tests {
             let decoded = Frame::decode(&frame).unwrap();
             prop_assert_eq!(decoded, payload);
         }
+
+        #[test]
+        fn frame_roundtrip_across_extended_boundary_prop(
+            payload in prop::collection::vec(any::<u8>(), 200..600)
+        ) {
+            let frame = Frame::encode(&payload).unwrap();
+            // The extended form is chosen exactly when the length needs it
+            prop_assert_eq!(frame[3..5] == [0xFF, 0xFF], payload.len() > 255);
+            prop_assert_eq!(Frame::decode(&frame).unwrap(), payload.clone());
+
+            // Extended and normal frames split apart back to back
+            let short = Frame::encode(&[0x01]).unwrap();
+            let mut buf = frame.clone();
+            buf.extend_from_slice(&short);
+            prop_assert_eq!(Frame::split(&buf), vec![&frame[..], &short[..]]);
+        }
+    }
+
+    #[test]
+    fn extended_frame_layout() {
+        let payload = vec![0xAB; 0x0123];
+        let frame = Frame::encode(&payload).unwrap();
+        // LENM LENL LCS with LENM + LENL + LCS == 0
+        assert_eq!(
+            &frame[..8],
+            &[0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x01, 0x23, 0xDC]
+        );
+        assert_eq!(frame.len(), payload.len() + 10);
+        assert_eq!(*frame.last().unwrap(), 0x00);
+    }
+
+    #[test]
+    fn extended_frame_errors() {
+        let mut frame = Frame::encode(&[0x5A; 300]).unwrap();
+        frame[7] ^= 0x01; // LCS
+        assert!(matches!(
+            Frame::decode(&frame),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+
+        let frame = Frame::encode(&[0x5A; 300]).unwrap();
+        assert!(matches!(
+            Frame::decode(&frame[..frame.len() - 1]),
+            Err(Error::InvalidLength { .. })
+        ));
+
+        assert!(matches!(
+            Frame::encode(&vec![0; 0x1_0000]),
+            Err(Error::InvalidLength { .. })
+        ));
+    }
+
+    #[test]
+    fn classify_link_frames() {
+        assert_eq!(
+            Frame::kind(&crate::constants::LINK_ACK_FRAME),
+            FrameKind::Ack
+        );
+        assert_eq!(
+            Frame::kind(&crate::constants::LINK_NACK_FRAME),
+            FrameKind::Nack
+        );
+        // The error frame is a well-formed frame carrying 0x7F
+        let error = Frame::encode(&[0x7F]).unwrap();
+        assert_eq!(error, crate::constants::LINK_ERROR_FRAME);
+        assert_eq!(Frame::kind(&error), FrameKind::Error);
+        assert_eq!(
+            Frame::kind(&Frame::encode(&[0x01]).unwrap()),
+            FrameKind::Data
+        );
+    }
+
+    #[test]
+    fn split_back_to_back_frames() {
+        let a = Frame::encode(&[0x01, 0x02]).unwrap();
+        let b = Frame::encode(&[0x03]).unwrap();
+        let mut buf = vec![0xAA];
+        buf.extend_from_slice(&a);
+        buf.extend_from_slice(&b);
+        buf.extend_from_slice(&b[..4]); // truncated tail
+
+        let frames = Frame::split(&buf);
+        assert_eq!(frames, vec![&a[..], &b[..]]);
     }
 
     #[test]
@@ -0,0 +1,149 @@
+// libpafe-rs/libpafe/src/protocol/link.rs
+
+use crate::protocol::frame::{Frame, FrameKind};
+use crate::{Error, Result};
+
+/// NACKs sent for one reply before a checksum failure is given up on.
+pub const LINK_MAX_NACKS: u8 = 2;
+
+/// What the caller of [`Link::on_frame`] has to do next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkAction {
+    /// Read the next frame and feed it back in.
+    Receive,
+    /// Send [`LINK_NACK_FRAME`], then read the retransmitted frame.
+    ///
+    /// [`LINK_NACK_FRAME`]: crate::constants::LINK_NACK_FRAME
+    Nack,
+    /// The reply is complete; hand these bytes to the device model.
+    Done(Vec<u8>),
+}
+
+/// Link-layer state machine for receiving the reply to one command.
+///
+/// PN53x-style chips (RCS956, port100) first ACK a command, then send
+/// the response frame. A response whose checksums fail is NACKed so the
+/// chip retransmits it, and an error frame becomes `Error::ErrorFrame`.
+/// Readers that never ACK (S310/S320) pass straight through: their first
+/// frame is the reply and is returned unchanged. The machine does no
+/// I/O, so the sync and async devices drive it alike.
+#[derive(Debug, Default)]
+pub struct Link {
+    acked: bool,
+    nacks: u8,
+}
+
+impl Link {
+    /// Start receiving the reply to a command that was just sent.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the next chunk of bytes read from the transport.
+    pub fn on_frame(&mut self, raw: Vec<u8>) -> Result<LinkAction> {
+        // Some transports return the ACK and the response in one read
+        let raw = match raw.strip_prefix(&crate::constants::LINK_ACK_FRAME[..]) {
+            Some(rest) if !rest.is_empty() => {
+                self.acked = true;
+                rest.to_vec()
+            }
+            _ => raw,
+        };
+
+        match Frame::kind(&raw) {
+            FrameKind::Ack => {
+                self.acked = true;
+                Ok(LinkAction::Receive)
+            }
+            FrameKind::Nack => Err(Error::FrameFormat("unexpected NACK from the device".into())),
+            FrameKind::Error => Err(Error::ErrorFrame),
+            // Only a chip that ACKed speaks the link protocol and
+            // understands a NACK
+            FrameKind::Data if !self.acked => Ok(LinkAction::Done(raw)),
+            FrameKind::Data => match Frame::decode(&raw) {
+                Err(e @ Error::ChecksumMismatch { .. }) => {
+                    if self.nacks >= LINK_MAX_NACKS {
+                        return Err(e);
+                    }
+                    self.nacks += 1;
+                    Ok(LinkAction::Nack)
+                }
+                // Other shapes (port100 extended frames, vendor wrappers)
+                // are left to the device model
+                _ => Ok(LinkAction::Done(raw)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{LINK_ACK_FRAME, LINK_ERROR_FRAME, LINK_NACK_FRAME};
+
+    fn data() -> Vec<u8> {
+        Frame::encode(&[0xD5, 0x4B, 0x00]).unwrap()
+    }
+
+    fn corrupted() -> Vec<u8> {
+        let mut frame = data();
+        let dcs_idx = frame.len() - 2;
+        frame[dcs_idx] ^= 0xFF;
+        frame
+    }
+
+    #[test]
+    fn ack_then_data() {
+        let mut link = Link::new();
+        assert_eq!(
+            link.on_frame(LINK_ACK_FRAME.to_vec()).unwrap(),
+            LinkAction::Receive
+        );
+        assert_eq!(link.on_frame(data()).unwrap(), LinkAction::Done(data()));
+
+        // ACK and response delivered in a single read
+        let mut both = LINK_ACK_FRAME.to_vec();
+        both.extend_from_slice(&data());
+        assert_eq!(
+            Link::new().on_frame(both).unwrap(),
+            LinkAction::Done(data())
+        );
+    }
+
+    #[test]
+    fn nacks_corrupted_response_until_limit() {
+        let mut link = Link::new();
+        link.on_frame(LINK_ACK_FRAME.to_vec()).unwrap();
+        for _ in 0..LINK_MAX_NACKS {
+            assert_eq!(link.on_frame(corrupted()).unwrap(), LinkAction::Nack);
+        }
+        assert!(matches!(
+            link.on_frame(corrupted()),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn passes_through_without_ack() {
+        // Readers that never ACK get their reply back untouched, even
+        // when it is garbled
+        assert_eq!(
+            Link::new().on_frame(corrupted()).unwrap(),
+            LinkAction::Done(corrupted())
+        );
+    }
+
+    #[test]
+    fn error_and_nack_frames_fail() {
+        let mut link = Link::new();
+        link.on_frame(LINK_ACK_FRAME.to_vec()).unwrap();
+        assert!(matches!(
+            link.on_frame(LINK_ERROR_FRAME.to_vec()),
+            Err(Error::ErrorFrame)
+        ));
+        assert!(matches!(
+            Link::new().on_frame(LINK_NACK_FRAME.to_vec()),
+            Err(Error::FrameFormat(_))
+        ));
+    }
+}
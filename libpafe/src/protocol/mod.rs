@@ -4,10 +4,21 @@ pub mod checksum;
 pub mod codec;
 pub mod commands;
 pub mod frame;
+pub mod link;
 pub mod parser;
 pub mod responses;
+pub mod status;
 
 pub use checksum::{dcs, lcs};
-pub use commands::*;
-pub use frame::Frame;
-pub use responses::*;
+pub use commands::{
+    Command, encode_polling, encode_request_response, encode_request_system_code,
+    encode_search_service_code,
+};
+pub use frame::{Frame, FrameKind};
+pub use link::{Link, LinkAction};
+pub use responses::{
+    BlockInformation, Response, SearchServiceEntry, decode_polling, decode_read,
+    decode_request_block_information, decode_request_block_information_ex, decode_request_response,
+    decode_request_service, decode_request_system_code, decode_search_service_code, decode_write,
+};
+pub use status::StatusFlag;
@@ -0,0 +1,153 @@
+// libpafe-rs/libpafe/src/protocol/responses/block_info.rs
+
+use crate::protocol::parser;
+use crate::types::Idm;
+use crate::{Error, Result};
+
+/// Per-node entry of a RequestBlockInformationEx response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInformation {
+    /// Number of blocks assigned to the node
+    pub assigned_blocks: u16,
+    /// Number of free blocks available to the node
+    pub free_blocks: u16,
+}
+
+/// Decode RequestBlockInformation response payload (response code = 0x0F)
+/// Layout: response_code(1) + idm(8) + count(1) + block_info(N*2)
+///
+/// Each entry is the number of blocks assigned to a service node or the
+/// number of free blocks of an area node, in request order.
+pub fn decode_request_block_information(data: &[u8]) -> Result<(Idm, Vec<u16>)> {
+    const MIN_LEN: usize = 1 + 8 + 1; // 10
+    parser::ensure_len(data, MIN_LEN)?;
+
+    let expected = 0x0Eu8 + 1;
+    parser::expect_response_code(data, expected)?;
+
+    let idm = parser::idm_at(data, 1)?;
+    let count = parser::byte_at(data, 9)? as usize;
+    parser::ensure_len(data, 10 + count * 2)?;
+
+    let mut counts = Vec::with_capacity(count);
+    for i in 0..count {
+        counts.push(parser::le_u16_at(data, 10 + i * 2)?);
+    }
+
+    Ok((idm, counts))
+}
+
+/// Decode RequestBlockInformationEx response payload (response code = 0x1F)
+/// Layout: response_code(1) + idm(8) + status1(1) + status2(1) + count(1)
+///         + block_info(N*4: assigned(2) + free(2))
+///
+/// The count and block information are only present when both status
+/// flags are zero; a non-zero status is surfaced via [`Error::from_status`].
+pub fn decode_request_block_information_ex(data: &[u8]) -> Result<(Idm, Vec<BlockInformation>)> {
+    const MIN_LEN: usize = 1 + 8 + 1 + 1; // 11
+    parser::ensure_len(data, MIN_LEN)?;
+
+    let expected = 0x1Eu8 + 1;
+    parser::expect_response_code(data, expected)?;
+
+    let idm = parser::idm_at(data, 1)?;
+    let status1 = parser::byte_at(data, 9)?;
+    let status2 = parser::byte_at(data, 10)?;
+    if status1 != 0 || status2 != 0 {
+        return Err(Error::from_status(status1, status2));
+    }
+
+    let count = parser::byte_at(data, 11)? as usize;
+    parser::ensure_len(data, 12 + count * 4)?;
+
+    let mut info = Vec::with_capacity(count);
+    for i in 0..count {
+        let off = 12 + i * 4;
+        info.push(BlockInformation {
+            assigned_blocks: parser::le_u16_at(data, off)?,
+            free_blocks: parser::le_u16_at(data, off + 2)?,
+        });
+    }
+
+    Ok((idm, info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_request_block_information_ok() {
+        let mut data = vec![0x0F];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data.push(2);
+        data.extend_from_slice(&0x0010u16.to_le_bytes());
+        data.extend_from_slice(&0x0140u16.to_le_bytes());
+
+        let (idm, counts) = decode_request_block_information(&data).unwrap();
+        assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(counts, vec![0x0010, 0x0140]);
+    }
+
+    #[test]
+    fn decode_request_block_information_truncated_list() {
+        let mut data = vec![0x0F];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data.push(2);
+        data.extend_from_slice(&0x0010u16.to_le_bytes());
+
+        match decode_request_block_information(&data) {
+            Err(crate::Error::InvalidLength { .. }) => {}
+            other => panic!("expected InvalidLength, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_request_block_information_ex_ok() {
+        let mut data = vec![0x1F];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data.push(0); // status1
+        data.push(0); // status2
+        data.push(1);
+        data.extend_from_slice(&0x0014u16.to_le_bytes());
+        data.extend_from_slice(&0x0003u16.to_le_bytes());
+
+        let (idm, info) = decode_request_block_information_ex(&data).unwrap();
+        assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            info,
+            vec![BlockInformation {
+                assigned_blocks: 0x14,
+                free_blocks: 0x03,
+            }]
+        );
+    }
+
+    #[test]
+    fn decode_request_block_information_ex_status_error() {
+        let mut data = vec![0x1F];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data.push(0xFF);
+        data.push(0xA1);
+
+        match decode_request_block_information_ex(&data) {
+            Err(crate::Error::FelicaStatus {
+                status1: 0xFF,
+                status2: 0xA1,
+            }) => {}
+            other => panic!("expected FelicaStatus, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_request_block_information_ex_unexpected_response() {
+        let data = vec![0x0F, 1, 2, 3, 4, 5, 6, 7, 8, 0, 0];
+        match decode_request_block_information_ex(&data) {
+            Err(crate::Error::UnexpectedResponse {
+                expected: 0x1F,
+                actual: 0x0F,
+            }) => {}
+            other => panic!("expected UnexpectedResponse, got {:?}", other),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 // libpafe-rs/libpafe/src/protocol/responses/mod.rs
 
+pub mod block_info;
 pub mod polling;
 pub mod read;
 pub mod search;
@@ -7,9 +8,12 @@ pub mod service;
 pub mod system;
 pub mod write;
 
+pub use block_info::{
+    BlockInformation, decode_request_block_information, decode_request_block_information_ex,
+};
 pub use polling::decode_polling;
 pub use read::decode_read;
-pub use search::decode_search_service_code;
+pub use search::{SearchServiceEntry, decode_search_service_code};
 pub use service::{decode_request_response, decode_request_service};
 pub use system::decode_request_system_code;
 pub use write::decode_write;
@@ -18,10 +22,11 @@ pub use write::decode_write;
 /// `protocol::responses::<name>.rs` and are dispatched here.
 #[derive(Debug, Clone)]
 pub enum Response {
+    /// Raw Request Data is present only for a non-zero request code
     Polling {
         idm: crate::types::Idm,
         pmm: crate::types::Pmm,
-        system_code: crate::types::SystemCode,
+        request_data: Option<[u8; 2]>,
     },
     ReadWithoutEncryption {
         idm: crate::types::Idm,
@@ -44,13 +49,39 @@ pub enum Response {
         idm: crate::types::Idm,
         system_codes: Vec<crate::types::SystemCode>,
     },
+    /// Node at the requested index, or `None` past the last node
     SearchServiceCode {
         idm: crate::types::Idm,
-        area_or_service_code: Option<u16>,
+        entry: Option<SearchServiceEntry>,
+    },
+    /// Block counts in request order (assigned for services, free for areas)
+    RequestBlockInformation {
+        idm: crate::types::Idm,
+        block_counts: Vec<u16>,
+    },
+    /// Assigned and free block counts in request order
+    RequestBlockInformationEx {
+        idm: crate::types::Idm,
+        block_info: Vec<BlockInformation>,
     },
 }
 
 impl Response {
+    /// IDm of the card that sent the response.
+    pub fn idm(&self) -> &crate::types::Idm {
+        match self {
+            Self::Polling { idm, .. }
+            | Self::ReadWithoutEncryption { idm, .. }
+            | Self::WriteWithoutEncryption { idm, .. }
+            | Self::RequestService { idm, .. }
+            | Self::RequestResponse { idm, .. }
+            | Self::RequestSystemCode { idm, .. }
+            | Self::SearchServiceCode { idm, .. }
+            | Self::RequestBlockInformation { idm, .. }
+            | Self::RequestBlockInformationEx { idm, .. } => idm,
+        }
+    }
+
     /// Decode a response payload (including response code) for the given
     /// expected command code.
     pub fn decode(expected_cmd: u8, data: &[u8]) -> crate::Result<Self> {
@@ -65,11 +96,11 @@ impl Response {
 
         match expected_cmd {
             0x00 => {
-                let (idm, pmm, sys) = polling::decode_polling(data)?;
+                let (idm, pmm, request_data) = polling::decode_polling(data)?;
                 Ok(Self::Polling {
                     idm,
                     pmm,
-                    system_code: sys,
+                    request_data,
                 })
             }
             0x06 => {
@@ -100,11 +131,16 @@ impl Response {
                 })
             }
             0x0a => {
-                let (idm, code) = search::decode_search_service_code(data)?;
-                Ok(Self::SearchServiceCode {
-                    idm,
-                    area_or_service_code: code,
-                })
+                let (idm, entry) = search::decode_search_service_code(data)?;
+                Ok(Self::SearchServiceCode { idm, entry })
+            }
+            0x0e => {
+                let (idm, block_counts) = block_info::decode_request_block_information(data)?;
+                Ok(Self::RequestBlockInformation { idm, block_counts })
+            }
+            0x1e => {
+                let (idm, block_info) = block_info::decode_request_block_information_ex(data)?;
+                Ok(Self::RequestBlockInformationEx { idm, block_info })
             }
             _ => {
                 // Unknown command: report unexpected response using the first
@@ -130,6 +166,8 @@ impl Response {
             Response::RequestResponse { .. } => 0x05,
             Response::RequestSystemCode { .. } => 0x0D,
             Response::SearchServiceCode { .. } => 0x0B,
+            Response::RequestBlockInformation { .. } => 0x0F,
+            Response::RequestBlockInformationEx { .. } => 0x1F,
         }
     }
 }
@@ -151,11 +189,11 @@ mod tests {
             Response::Polling {
                 idm,
                 pmm,
-                system_code,
+                request_data,
             } => {
                 assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
                 assert_eq!(pmm.as_bytes(), &[9, 10, 11, 12, 13, 14, 15, 16]);
-                assert_eq!(system_code.as_u16(), 0x0a0b);
+                assert_eq!(request_data, Some(SystemCode::new(0x0a0b).to_le_bytes()));
             }
             other => panic!("unexpected response: {:?}", other),
         }
@@ -169,7 +207,7 @@ mod tests {
         fn response_decode_random_payloads_no_panic(v in prop::collection::vec(any::<u8>(), 0..64)) {
             use std::panic::{catch_unwind, AssertUnwindSafe};
             // List of command codes we support (command -> expected response = +1)
-            let cmds = [0x00u8, 0x06u8, 0x08u8, 0x02u8, 0x04u8, 0x0Cu8, 0x0Au8];
+            let cmds = [0x00u8, 0x06u8, 0x08u8, 0x02u8, 0x04u8, 0x0Cu8, 0x0Au8, 0x0Eu8, 0x1Eu8];
             for &cmd in &cmds {
                 let res = catch_unwind(AssertUnwindSafe(|| Response::decode(cmd, &v)));
                 // Should not panic
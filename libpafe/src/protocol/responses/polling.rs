@@ -1,13 +1,17 @@
 // libpafe-rs/libpafe/src/protocol/responses/polling.rs
 
-use crate::Result;
 use crate::protocol::parser;
-use crate::types::{Idm, Pmm, SystemCode};
+use crate::types::{Idm, Pmm};
+use crate::{Error, Result};
 
 /// Decode a Polling response payload (response code = 0x01)
-/// Layout: response_code(1) + idm(8) + pmm(8) + system_code(2)
-pub fn decode_polling(data: &[u8]) -> Result<(Idm, Pmm, SystemCode)> {
-    const MIN_LEN: usize = 1 + 8 + 8 + 2; // 19
+/// Layout: response_code(1) + idm(8) + pmm(8) [+ request_data(2)]
+///
+/// Request Data is only present when the Polling command carried a non-zero
+/// request code; its meaning depends on that code (see
+/// [`PollingRequest::decode_request_data`](crate::types::PollingRequest::decode_request_data)).
+pub fn decode_polling(data: &[u8]) -> Result<(Idm, Pmm, Option<[u8; 2]>)> {
+    const MIN_LEN: usize = 1 + 8 + 8; // 17
     parser::ensure_len(data, MIN_LEN)?;
 
     // Ensure we have at least a response byte and it matches expected
@@ -16,15 +20,24 @@ pub fn decode_polling(data: &[u8]) -> Result<(Idm, Pmm, SystemCode)> {
 
     let idm = parser::idm_at(data, 1)?;
     let pmm = parser::pmm_at(data, 9)?;
-    let sys = SystemCode::new(parser::le_u16_at(data, 17)?);
+    let request_data = match data.len() {
+        MIN_LEN => None,
+        len if len >= MIN_LEN + 2 => Some([data[17], data[18]]),
+        len => {
+            return Err(Error::InvalidLength {
+                expected: MIN_LEN + 2,
+                actual: len,
+            });
+        }
+    };
 
-    Ok((idm, pmm, sys))
+    Ok((idm, pmm, request_data))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Idm, Pmm, SystemCode};
+    use crate::types::SystemCode;
 
     #[test]
     fn decode_polling_ok() {
@@ -34,10 +47,36 @@ mod tests {
         data.extend_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]); // pmm
         data.extend_from_slice(&SystemCode::new(0x0a0b).to_le_bytes());
 
-        let (idm, pmm, sc) = decode_polling(&data).unwrap();
+        let (idm, pmm, request_data) = decode_polling(&data).unwrap();
         assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
         assert_eq!(pmm.as_bytes(), &[9, 10, 11, 12, 13, 14, 15, 16]);
-        assert_eq!(sc.as_u16(), 0x0a0b);
+        assert_eq!(request_data, Some([0x0b, 0x0a]));
+    }
+
+    #[test]
+    fn decode_polling_without_request_data() {
+        let mut data = vec![0x01];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]); // idm
+        data.extend_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]); // pmm
+
+        let (_, _, request_data) = decode_polling(&data).unwrap();
+        assert_eq!(request_data, None);
+    }
+
+    #[test]
+    fn decode_polling_truncated_request_data() {
+        let mut data = vec![0x01];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]); // idm
+        data.extend_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]); // pmm
+        data.push(0x0b);
+
+        match decode_polling(&data) {
+            Err(crate::Error::InvalidLength {
+                expected: 19,
+                actual: 18,
+            }) => {}
+            other => panic!("expected InvalidLength, got {:?}", other),
+        }
     }
 
     #[test]
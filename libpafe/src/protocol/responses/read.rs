@@ -6,8 +6,11 @@ use crate::{Error, Result};
 
 /// Decode ReadWithoutEncryption response payload (response code = 0x07)
 /// Layout: response_code(1) + idm(8) + status1(1) + status2(1) + block_count(1) + blocks(N*16)
+///
+/// Block count and blocks are only present when both status flags are zero;
+/// otherwise the status is returned with no blocks.
 pub fn decode_read(data: &[u8]) -> Result<(Idm, (u8, u8), Vec<BlockData>)> {
-    const MIN_LEN: usize = 1 + 8 + 1 + 1 + 1; // 12
+    const MIN_LEN: usize = 1 + 8 + 1 + 1; // 11
     parser::ensure_len(data, MIN_LEN)?;
 
     let expected = 0x06u8 + 1;
@@ -17,8 +20,10 @@ pub fn decode_read(data: &[u8]) -> Result<(Idm, (u8, u8), Vec<BlockData>)> {
     let status1 = parser::byte_at(data, 9)?;
     let status2 = parser::byte_at(data, 10)?;
 
+    // Error responses end after the status flags; hand the status back so
+    // the caller can report the failing block list element.
     if status1 != 0 || status2 != 0 {
-        return Err(Error::FelicaStatus { status1, status2 });
+        return Ok((idm, (status1, status2), Vec::new()));
     }
 
     let block_count = parser::byte_at(data, 11)? as usize;
@@ -84,17 +89,13 @@ mod tests {
     fn decode_read_status_error() {
         let mut data = vec![0x07];
         data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]); // idm
-        data.push(0xA4); // status1 non-zero
-        data.push(0x00); // status2
-        data.push(0); // block_count
+        data.push(0x01); // status1: first block list element
+        data.push(0xA8); // status2: illegal block number
 
-        match decode_read(&data) {
-            Err(crate::Error::FelicaStatus {
-                status1: 0xA4,
-                status2: 0x00,
-            }) => {}
-            other => panic!("expected FelicaStatus, got {:?}", other),
-        }
+        let (idm, status, blocks) = decode_read(&data).unwrap();
+        assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(status, (0x01, 0xA8));
+        assert!(blocks.is_empty());
     }
 
     #[test]
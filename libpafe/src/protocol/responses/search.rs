@@ -4,21 +4,57 @@ use crate::error::Result;
 use crate::protocol::parser;
 use crate::types::Idm;
 
+/// Node reported by SearchServiceCode for a given index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchServiceEntry {
+    /// Area code together with the end code of the range it covers
+    Area {
+        /// Area code (area number + attribute)
+        code: u16,
+        /// Last code of the range covered by the area
+        end_code: u16,
+    },
+    /// Service code
+    Service(u16),
+}
+
+impl SearchServiceEntry {
+    /// Area or service code of the entry.
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Area { code, .. } => *code,
+            Self::Service(code) => *code,
+        }
+    }
+}
+
+/// Code returned once the index runs past the last node.
+const SEARCH_END: u16 = 0xFFFF;
+
 /// Decode SearchServiceCode response (expected response code = 0x0B)
-/// Layout assumed: response_code(1) + idm(8) + present_flag(1) + optional service_code(2)
-pub fn decode_search_service_code(data: &[u8]) -> Result<(Idm, Option<u16>)> {
-    const MIN_LEN: usize = 1 + 8 + 1; // resp + idm + present_flag
+/// Layout: response_code(1) + idm(8) + code(2) [+ end_code(2) for areas]
+///
+/// Area codes (attribute bits 0b00000x) are followed by the end code of
+/// the area; service codes are returned alone. A code of 0xFFFF marks the
+/// end of the node list and is returned as `None`.
+pub fn decode_search_service_code(data: &[u8]) -> Result<(Idm, Option<SearchServiceEntry>)> {
+    const MIN_LEN: usize = 1 + 8 + 2; // resp + idm + code
     parser::ensure_len(data, MIN_LEN)?;
     // check response code is the expected (0x0A command => 0x0B response)
     parser::expect_response_code(data, 0x0B)?;
     let idm = parser::idm_at(data, 1)?;
-    let present_flag = parser::byte_at(data, 9)?;
-    if present_flag == 0 {
-        Ok((idm, None))
+    let code = parser::le_u16_at(data, 9)?;
+
+    if code == SEARCH_END {
+        return Ok((idm, None));
+    }
+
+    if code & 0x003e == 0 {
+        // must have 2 more bytes for the end code
+        let end_code = parser::le_u16_at(data, 11)?;
+        Ok((idm, Some(SearchServiceEntry::Area { code, end_code })))
     } else {
-        parser::ensure_len(data, MIN_LEN + 2)?; // must have 2 bytes for service code
-        let code = parser::le_u16_at(data, 10)?;
-        Ok((idm, Some(code)))
+        Ok((idm, Some(SearchServiceEntry::Service(code))))
     }
 }
 
@@ -28,25 +64,51 @@ mod tests {
     use crate::types::Idm;
 
     #[test]
-    fn decode_search_service_code_not_found() {
-        // resp + idm + present=0
+    fn decode_search_service_code_end_of_list() {
+        // resp + idm + 0xFFFF
         let mut v = vec![0x0B];
         v.extend_from_slice(&[9, 8, 7, 6, 5, 4, 3, 2]);
-        v.push(0);
+        v.extend_from_slice(&[0xFF, 0xFF]);
         let (idm, maybe) = decode_search_service_code(&v).expect("decode");
         assert_eq!(idm.as_bytes(), &[9, 8, 7, 6, 5, 4, 3, 2]);
         assert!(maybe.is_none());
     }
 
     #[test]
-    fn decode_search_service_code_found() {
+    fn decode_search_service_code_service() {
         let mut v = vec![0x0B];
         v.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
-        v.push(1);
-        v.extend_from_slice(&0x1234u16.to_le_bytes());
+        v.extend_from_slice(&0x090fu16.to_le_bytes());
         let (idm, maybe) = decode_search_service_code(&v).expect("decode");
-        assert_eq!(idm.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8]);
-        assert_eq!(maybe, Some(0x1234));
+        assert_eq!(idm, Idm::from_bytes([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(maybe, Some(SearchServiceEntry::Service(0x090f)));
+    }
+
+    #[test]
+    fn decode_search_service_code_area() {
+        let mut v = vec![0x0B];
+        v.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        v.extend_from_slice(&0x1000u16.to_le_bytes());
+        v.extend_from_slice(&0x17ffu16.to_le_bytes());
+        let (_, maybe) = decode_search_service_code(&v).expect("decode");
+        assert_eq!(
+            maybe,
+            Some(SearchServiceEntry::Area {
+                code: 0x1000,
+                end_code: 0x17ff,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_search_service_code_area_missing_end_code() {
+        let mut v = vec![0x0B];
+        v.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        v.extend_from_slice(&0x0001u16.to_le_bytes());
+        match decode_search_service_code(&v) {
+            Err(crate::Error::InvalidLength { .. }) => {}
+            other => panic!("expected InvalidLength, got {:?}", other),
+        }
     }
 
     #[test]
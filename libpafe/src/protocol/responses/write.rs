@@ -7,8 +7,8 @@ use crate::{Error, Result};
 /// Decode WriteWithoutEncryption response (response code = 0x09).
 /// Accepts single or multi-block responses; each block has a 2-byte
 /// status tuple (status1, status2). Returns the parsed Idm and the
-/// vector of status tuples. If any status is non-zero an immediate
-/// `Error::FelicaStatus` is returned.
+/// vector of status tuples. If any status is non-zero an immediate error is
+/// returned: for a single status pair, see [`Error::from_status`].
 pub fn decode_write(data: &[u8]) -> Result<(Idm, Vec<(u8, u8)>)> {
     // Minimal: response_code(1) + idm(8) + at least one status pair(2)
     const MIN_LEN: usize = 1 + 8 + 2;
@@ -41,10 +41,7 @@ pub fn decode_write(data: &[u8]) -> Result<(Idm, Vec<(u8, u8)>)> {
     for (i, &(s1, s2)) in statuses.iter().enumerate() {
         if s1 != 0 || s2 != 0 {
             if statuses.len() == 1 {
-                return Err(Error::FelicaStatus {
-                    status1: s1,
-                    status2: s2,
-                });
+                return Err(Error::from_status(s1, s2));
             } else {
                 return Err(Error::FelicaBlockStatus {
                     index: i,
@@ -90,6 +87,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn decode_write_status_names_failing_element() {
+        let mut data = vec![0x09];
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data.push(0x02); // second block list element
+        data.push(0xA5); // access not allowed
+
+        match decode_write(&data) {
+            Err(crate::Error::FelicaBlockStatus {
+                index: 1,
+                status1: 0x02,
+                status2: 0xA5,
+            }) => {}
+            other => panic!("expected FelicaBlockStatus, got {:?}", other),
+        }
+    }
+
     #[test]
     fn decode_write_too_short() {
         let data = vec![0x09, 1, 2, 3];
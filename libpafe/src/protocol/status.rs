@@ -0,0 +1,174 @@
+// libpafe-rs/libpafe/src/protocol/status.rs
+
+use std::fmt;
+
+/// Decoded FeliCa status flag 2 (the error code of a status pair).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusFlag {
+    /// 0x00
+    Success,
+    /// 0x01
+    PurseOutOfRange,
+    /// 0x02
+    CashbackExceeded,
+    /// 0x70
+    MemoryError,
+    /// 0x71
+    RewriteCountExceeded,
+    /// 0xA1
+    IllegalNumberOfService,
+    /// 0xA2
+    IllegalNumberOfBlock,
+    /// 0xA3
+    IllegalServiceCodeOrder,
+    /// 0xA4
+    IllegalServiceType,
+    /// 0xA5
+    AccessNotAllowed,
+    /// 0xA6
+    IllegalServiceCodeList,
+    /// 0xA7
+    IllegalAccessMode,
+    /// 0xA8
+    IllegalBlockNumber,
+    /// 0xA9
+    WriteFailure,
+    /// 0xAA
+    KeyChangeFailure,
+    /// 0xAB
+    IllegalPackageParityOrMac,
+    /// 0xAC
+    IllegalParameter,
+    /// 0xAD
+    ServiceExists,
+    /// 0xAE
+    IllegalSystemCode,
+    /// 0xAF
+    TooManyCyclicWrites,
+    /// 0xB1
+    MacMismatch,
+    /// 0xB2
+    MacRequired,
+    /// 0xC0
+    IllegalPackageIdentifier,
+    /// 0xC1
+    PackageParameterMismatch,
+    /// 0xC2
+    CommandDisabled,
+    /// Any other value
+    Unknown(u8),
+}
+
+impl StatusFlag {
+    /// Human readable description of the status code
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::PurseOutOfRange => "purse data underflow or overflow",
+            Self::CashbackExceeded => "cashback data exceeds the purse",
+            Self::MemoryError => "memory error (fatal)",
+            Self::RewriteCountExceeded => "number of memory rewrites exceeded",
+            Self::IllegalNumberOfService => "illegal number of services",
+            Self::IllegalNumberOfBlock => "illegal number of blocks",
+            Self::IllegalServiceCodeOrder => "block list refers to an illegal service code order",
+            Self::IllegalServiceType => "illegal service type",
+            Self::AccessNotAllowed => "access is not allowed",
+            Self::IllegalServiceCodeList => "illegal service code list",
+            Self::IllegalAccessMode => "illegal block list access mode",
+            Self::IllegalBlockNumber => "illegal block number",
+            Self::WriteFailure => "data write failure",
+            Self::KeyChangeFailure => "key change failure",
+            Self::IllegalPackageParityOrMac => "illegal package parity or MAC",
+            Self::IllegalParameter => "illegal parameter",
+            Self::ServiceExists => "service already exists",
+            Self::IllegalSystemCode => "illegal system code",
+            Self::TooManyCyclicWrites => "too many simultaneous cyclic write operations",
+            Self::MacMismatch => "MAC does not match",
+            Self::MacRequired => "write access requires MAC",
+            Self::IllegalPackageIdentifier => "illegal package identifier",
+            Self::PackageParameterMismatch => "parameters inside and outside the package differ",
+            Self::CommandDisabled => "command is already disabled",
+            Self::Unknown(_) => "unknown status",
+        }
+    }
+}
+
+impl From<u8> for StatusFlag {
+    fn from(status2: u8) -> Self {
+        match status2 {
+            0x00 => Self::Success,
+            0x01 => Self::PurseOutOfRange,
+            0x02 => Self::CashbackExceeded,
+            0x70 => Self::MemoryError,
+            0x71 => Self::RewriteCountExceeded,
+            0xA1 => Self::IllegalNumberOfService,
+            0xA2 => Self::IllegalNumberOfBlock,
+            0xA3 => Self::IllegalServiceCodeOrder,
+            0xA4 => Self::IllegalServiceType,
+            0xA5 => Self::AccessNotAllowed,
+            0xA6 => Self::IllegalServiceCodeList,
+            0xA7 => Self::IllegalAccessMode,
+            0xA8 => Self::IllegalBlockNumber,
+            0xA9 => Self::WriteFailure,
+            0xAA => Self::KeyChangeFailure,
+            0xAB => Self::IllegalPackageParityOrMac,
+            0xAC => Self::IllegalParameter,
+            0xAD => Self::ServiceExists,
+            0xAE => Self::IllegalSystemCode,
+            0xAF => Self::TooManyCyclicWrites,
+            0xB1 => Self::MacMismatch,
+            0xB2 => Self::MacRequired,
+            0xC0 => Self::IllegalPackageIdentifier,
+            0xC1 => Self::PackageParameterMismatch,
+            0xC2 => Self::CommandDisabled,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl fmt::Display for StatusFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(code) => write!(f, "unknown status {code:#04x}"),
+            other => f.write_str(other.description()),
+        }
+    }
+}
+
+/// Index of the block list element that status flag 1 points at.
+///
+/// Status flag 1 sets bit `n` for an error in element `n`; 0x00 (success),
+/// 0xFF (error not tied to an element) and other multi-bit values yield
+/// `None`.
+pub fn failing_element(status1: u8) -> Option<usize> {
+    if status1.count_ones() == 1 {
+        Some(status1.trailing_zeros() as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_flag_decodes_known_codes() {
+        assert_eq!(StatusFlag::from(0xA5), StatusFlag::AccessNotAllowed);
+        assert_eq!(StatusFlag::from(0xB2), StatusFlag::MacRequired);
+        assert_eq!(StatusFlag::from(0xC1), StatusFlag::PackageParameterMismatch);
+        assert_eq!(StatusFlag::from(0x5A), StatusFlag::Unknown(0x5A));
+        assert_eq!(StatusFlag::from(0xA8).to_string(), "illegal block number");
+        assert_eq!(StatusFlag::from(0x5A).to_string(), "unknown status 0x5a");
+    }
+
+    #[test]
+    fn failing_element_from_status1() {
+        assert_eq!(failing_element(0x00), None);
+        assert_eq!(failing_element(0x01), Some(0));
+        assert_eq!(failing_element(0x04), Some(2));
+        assert_eq!(failing_element(0x80), Some(7));
+        assert_eq!(failing_element(0xFF), None);
+        assert_eq!(failing_element(0xA4), None);
+    }
+}
@@ -4,6 +4,9 @@
 //! crate and tests/ directory can reuse the same logic.
 #![allow(dead_code)]
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::{Result, device, transport, types};
 
 /// Build a MockTransport pre-seeded with the given framed responses and
@@ -45,3 +48,177 @@ pub fn seed_init_and_frames(mock: &mut transport::mock::MockTransport, frames: V
         mock.push_response(f);
     }
 }
+
+/// Transport handing every call to a MockTransport the test keeps a
+/// handle on, so what a Device sent can be inspected after the Device
+/// took ownership of its transport.
+#[doc(hidden)]
+pub struct SharedMock(pub Rc<RefCell<transport::mock::MockTransport>>);
+
+impl transport::traits::Transport for SharedMock {
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        self.0.borrow_mut().send(data)
+    }
+
+    fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
+        self.0.borrow_mut().receive(timeout_ms)
+    }
+
+    fn device_type(&self) -> Result<types::DeviceType> {
+        self.0.borrow().device_type()
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.0.borrow_mut().reset()
+    }
+
+    fn vendor_control_write(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        self.0
+            .borrow_mut()
+            .vendor_control_write(request, value, index, data)
+    }
+
+    fn vendor_control_read(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        timeout_ms: u64,
+    ) -> Result<Vec<u8>> {
+        self.0
+            .borrow_mut()
+            .vendor_control_read(request, value, index, timeout_ms)
+    }
+}
+
+/// Initialized S320 device over a [`SharedMock`] seeded with the init
+/// ack and `frames`, together with the mock for inspecting `sent`. The
+/// initialization traffic is cleared from `sent`; retries are off as in
+/// [`mock_device`].
+#[doc(hidden)]
+pub fn shared_mock_device(
+    frames: Vec<Vec<u8>>,
+) -> (
+    device::Device<device::Initialized>,
+    Rc<RefCell<transport::mock::MockTransport>>,
+) {
+    let mock = Rc::new(RefCell::new(transport::mock::MockTransport::new(
+        types::DeviceType::S320,
+    )));
+    seed_init_and_frames(&mut mock.borrow_mut(), frames);
+    let device = device::Device::new_with_transport(Box::new(SharedMock(mock.clone())))
+        .map(|d| d.with_retry_policy(device::RetryPolicy::none()))
+        .and_then(|d| d.initialize())
+        .expect("mock device initializes");
+    mock.borrow_mut().sent.clear();
+    (device, mock)
+}
+
+/// IDm of the card the frame builders below answer for in most tests.
+#[doc(hidden)]
+pub const TEST_IDM: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+/// Framed FeliCa response: response `code`, `idm`, then `tail`.
+#[doc(hidden)]
+pub fn response_frame(code: u8, idm: [u8; 8], tail: &[u8]) -> Vec<u8> {
+    let mut payload = vec![code];
+    payload.extend_from_slice(&idm);
+    payload.extend_from_slice(tail);
+    crate::protocol::Frame::encode(&payload).expect("test frame fits")
+}
+
+/// Polling response from `idm` with a zero PMm and request data
+/// `00 03`.
+#[doc(hidden)]
+pub fn polling_frame(idm: [u8; 8]) -> Vec<u8> {
+    let mut tail = vec![0; 8];
+    tail.extend_from_slice(&[0x00, 0x03]);
+    response_frame(0x01, idm, &tail)
+}
+
+/// Request Response reply reporting mode 0.
+#[doc(hidden)]
+pub fn response_mode_frame(idm: [u8; 8]) -> Vec<u8> {
+    response_frame(0x05, idm, &[0x00])
+}
+
+/// Successful ReadWithoutEncryption response carrying `blocks`.
+#[doc(hidden)]
+pub fn read_frame(idm: [u8; 8], blocks: &[[u8; 16]]) -> Vec<u8> {
+    let mut tail = vec![0, 0, blocks.len() as u8];
+    for block in blocks {
+        tail.extend_from_slice(block);
+    }
+    response_frame(0x07, idm, &tail)
+}
+
+/// [`read_frame`] with every block filled with one byte of `fills`.
+#[doc(hidden)]
+pub fn read_fill_frame(idm: [u8; 8], fills: &[u8]) -> Vec<u8> {
+    let blocks: Vec<[u8; 16]> = fills.iter().map(|&f| [f; 16]).collect();
+    read_frame(idm, &blocks)
+}
+
+/// Response carrying only a status pair, e.g. a write reply (`0x09`)
+/// or a failed read (`0x07`).
+#[doc(hidden)]
+pub fn status_frame(code: u8, idm: [u8; 8], status1: u8, status2: u8) -> Vec<u8> {
+    response_frame(code, idm, &[status1, status2])
+}
+
+/// Initialized S320 device over a MockTransport seeded with the init ack
+/// and `frames`. Retries are off, so each frame answers one command.
+#[doc(hidden)]
+pub fn mock_device(frames: Vec<Vec<u8>>) -> device::Device<device::Initialized> {
+    let mut mock = transport::mock::MockTransport::new(types::DeviceType::S320);
+    seed_init_and_frames(&mut mock, frames);
+    device::Device::new_with_transport(Box::new(mock))
+        .map(|d| d.with_retry_policy(device::RetryPolicy::none()))
+        .and_then(|d| d.initialize())
+        .expect("mock device initializes")
+}
+
+/// Async counterpart of [`mock_device`].
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub async fn async_mock_device(frames: Vec<Vec<u8>>) -> device::AsyncDevice<device::Initialized> {
+    let mut mock = transport::mock::MockTransport::new(types::DeviceType::S320);
+    seed_init_and_frames(&mut mock, frames);
+    device::AsyncDevice::new_with_transport(Box::new(mock))
+        .expect("mock device opens")
+        .with_retry_policy(device::RetryPolicy::none())
+        .initialize()
+        .await
+        .expect("mock device initializes")
+}
+
+/// Card with [`TEST_IDM`], a zero PMm and system code `0x0003`.
+#[doc(hidden)]
+pub fn test_card() -> crate::card::Card {
+    test_card_with_ic(0x00)
+}
+
+/// [`test_card`] whose PMm reports `ic_type` (ROM type byte 1), which
+/// selects the per-command block limits.
+#[doc(hidden)]
+pub fn test_card_with_ic(ic_type: u8) -> crate::card::Card {
+    crate::card::Card::new(
+        types::Idm::from_bytes(TEST_IDM),
+        types::Pmm::from_bytes([0, ic_type, 0, 0, 0, 0, 0, 0]),
+        types::SystemCode::new(0x0003),
+    )
+}
+
+/// Read elements for blocks `0..n` of the first service.
+#[doc(hidden)]
+pub fn block_elements(n: u16) -> Vec<types::BlockElement> {
+    (0..n)
+        .map(|b| types::BlockElement::new(0, types::AccessMode::Normal, b))
+        .collect()
+}
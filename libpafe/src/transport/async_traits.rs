@@ -0,0 +1,69 @@
+// libpafe-rs/libpafe/src/transport/async_traits.rs
+
+use crate::Result;
+use crate::types::DeviceType;
+
+/// Async counterpart of [`Transport`] used by `AsyncDevice`.
+///
+/// Methods mirror the blocking trait; vendor control transfers fall back
+/// to `send`/`receive` the same way.
+///
+/// [`Transport`]: crate::transport::Transport
+#[async_trait::async_trait]
+pub trait AsyncTransport: Send {
+    /// Send raw bytes to the device
+    async fn send(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Receive raw bytes from the device with a timeout in milliseconds
+    async fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>>;
+
+    /// Query the detected device type
+    fn device_type(&self) -> Result<DeviceType>;
+
+    /// Perform a transport-level reset
+    async fn reset(&mut self) -> Result<()>;
+
+    /// Vendor-specific control write with explicit USB
+    /// `request`/`value`/`index` fields. Default falls back to `send`.
+    async fn vendor_control_write(
+        &mut self,
+        _request: u8,
+        _value: u16,
+        _index: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        self.send(data).await
+    }
+
+    /// Vendor-specific control read with explicit request/value/index and
+    /// timeout. Default falls back to `receive`.
+    async fn vendor_control_read(
+        &mut self,
+        _request: u8,
+        _value: u16,
+        _index: u16,
+        timeout_ms: u64,
+    ) -> Result<Vec<u8>> {
+        self.receive(timeout_ms).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    #[tokio::test]
+    async fn mock_implements_async_transport() {
+        let mut m = MockTransport::new(DeviceType::S320);
+        m.push_response(vec![0x01, 0x02]);
+        AsyncTransport::send(&mut m, &[0x10]).await.unwrap();
+        let r = AsyncTransport::receive(&mut m, 1000).await.unwrap();
+        assert_eq!(r, vec![0x01, 0x02]);
+        assert_eq!(m.sent, vec![vec![0x10]]);
+        assert!(matches!(
+            AsyncTransport::receive(&mut m, 1000).await,
+            Err(crate::Error::Timeout)
+        ));
+    }
+}
@@ -128,6 +128,48 @@ impl Transport for MockTransport {
     }
 }
 
+/// The mock answers immediately, so the async implementation simply
+/// forwards to the blocking one.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::transport::AsyncTransport for MockTransport {
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        Transport::send(self, data)
+    }
+
+    async fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
+        Transport::receive(self, timeout_ms)
+    }
+
+    fn device_type(&self) -> Result<DeviceType> {
+        Transport::device_type(self)
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        Transport::reset(self)
+    }
+
+    async fn vendor_control_write(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        Transport::vendor_control_write(self, request, value, index, data)
+    }
+
+    async fn vendor_control_read(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        timeout_ms: u64,
+    ) -> Result<Vec<u8>> {
+        Transport::vendor_control_read(self, request, value, index, timeout_ms)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,17 @@
 // libpafe-rs/libpafe/src/transport/mod.rs
 
+#[cfg(feature = "async")]
+pub mod async_traits;
 pub mod mock;
 pub mod traits;
 #[cfg(feature = "usb")]
 pub mod usb;
 
+#[cfg(feature = "async")]
+pub use async_traits::AsyncTransport;
 pub use mock::MockTransport;
 pub use traits::Transport;
+#[cfg(all(feature = "usb", feature = "async"))]
+pub use usb::AsyncUsbTransport;
 #[cfg(feature = "usb")]
 pub use usb::UsbTransport;
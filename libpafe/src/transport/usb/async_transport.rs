@@ -0,0 +1,138 @@
+// libpafe-rs/libpafe/src/transport/usb/async_transport.rs
+
+use std::sync::{Arc, Mutex};
+
+use crate::transport::traits::Transport;
+use crate::types::DeviceType;
+use crate::{Error, Result};
+
+use super::UsbTransport;
+
+/// [`AsyncTransport`] over a [`UsbTransport`]. rusb transfers block, so
+/// each call runs on tokio's blocking thread pool.
+///
+/// A transfer whose future is dropped still runs to completion; the next
+/// call waits for it before touching the device.
+///
+/// [`AsyncTransport`]: crate::transport::AsyncTransport
+pub struct AsyncUsbTransport {
+    inner: Arc<Mutex<UsbTransport>>,
+    device_type: DeviceType,
+}
+
+impl AsyncUsbTransport {
+    /// Wrap an opened USB transport.
+    pub fn new(transport: UsbTransport) -> Self {
+        Self {
+            device_type: transport.device_type,
+            inner: Arc::new(Mutex::new(transport)),
+        }
+    }
+
+    /// Open the first matching Sony PaSoRi device found on the bus (see
+    /// [`UsbTransport::open`]).
+    pub async fn open() -> Result<Self> {
+        tokio::task::spawn_blocking(UsbTransport::open)
+            .await
+            .map_err(join_error)?
+            .map(Self::new)
+    }
+
+    /// Run `op` on the blocking pool with exclusive use of the transport.
+    async fn run<T, F>(&self, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut UsbTransport) -> Result<T> + Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || {
+            // A poisoned lock means an earlier transfer panicked mid-way
+            let mut transport = inner.lock().map_err(|_| rusb::Error::Other)?;
+            op(&mut transport)
+        })
+        .await
+        .map_err(join_error)?
+    }
+}
+
+/// A blocking-pool task that panicked or was cancelled.
+fn join_error(_: tokio::task::JoinError) -> Error {
+    Error::Usb(rusb::Error::Other)
+}
+
+impl From<UsbTransport> for AsyncUsbTransport {
+    fn from(transport: UsbTransport) -> Self {
+        Self::new(transport)
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::transport::AsyncTransport for AsyncUsbTransport {
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        let data = data.to_vec();
+        self.run(move |t| t.send(&data)).await
+    }
+
+    async fn receive(&mut self, timeout_ms: u64) -> Result<Vec<u8>> {
+        self.run(move |t| t.receive(timeout_ms)).await
+    }
+
+    fn device_type(&self) -> Result<DeviceType> {
+        Ok(self.device_type)
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.run(|t| t.reset()).await
+    }
+
+    async fn vendor_control_write(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+    ) -> Result<()> {
+        let data = data.to_vec();
+        self.run(move |t| t.vendor_control_write(request, value, index, &data))
+            .await
+    }
+
+    async fn vendor_control_read(
+        &mut self,
+        request: u8,
+        value: u16,
+        index: u16,
+        timeout_ms: u64,
+    ) -> Result<Vec<u8>> {
+        self.run(move |t| t.vendor_control_read(request, value, index, timeout_ms))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{AsyncDevice, Uninitialized};
+
+    #[test]
+    fn async_device_accepts_usb_transport() {
+        // No reader is needed to check that the USB transport plugs into
+        // AsyncDevice.
+        fn device(transport: AsyncUsbTransport) -> Result<AsyncDevice<Uninitialized>> {
+            AsyncDevice::new_with_transport(Box::new(transport))
+        }
+        let _ = device;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires hardware (PaSoRi)"]
+    async fn async_device_initializes_over_usb() {
+        match AsyncUsbTransport::open().await {
+            Ok(transport) => {
+                let device = AsyncDevice::new_with_transport(Box::new(transport)).unwrap();
+                device.initialize().await.unwrap();
+            }
+            Err(e) => assert!(matches!(e, Error::DeviceNotFound)),
+        }
+    }
+}
@@ -12,7 +12,11 @@ use crate::{Error, Result};
 use rusb::UsbContext;
 use rusb::{Context, DeviceHandle, GlobalContext};
 
+#[cfg(feature = "async")]
+mod async_transport;
 mod descriptor;
+#[cfg(feature = "async")]
+pub use async_transport::AsyncUsbTransport;
 use descriptor::find_endpoints;
 
 /// Minimal UsbTransport implementation. This is intentionally small — it
@@ -84,57 +88,29 @@ impl Transport for UsbTransport {
         let timeout = Duration::from_millis(self.timeout_ms);
 
         if let Some(ep) = self.out_ep {
-            // Attempt several attempts to write to the OUT endpoint
-            // (bulk preferred, interrupt fallback). On repeated
-            // failures attempt to clear any halt/stall on the endpoint
-            // and back off between retries.
-            let mut last_rusb: Option<rusb::Error> = None;
-            for attempt in 1..=3 {
-                // If caller provided a PN532 host payload (TFI=0xD4), try
-                // to send a framed host packet first; fall back to raw.
-                if !data.is_empty() && data[0] == crate::constants::PN532_CMD_PREFIX_HOST {
-                    if let Ok(framed) = Frame::encode(data) {
-                        match self.handle.write_bulk(ep, &framed, timeout) {
-                            Ok(_) => return Ok(()),
-                            Err(e) => {
-                                last_rusb = Some(e);
-                                // Try interrupt as a fallback
-                                match self.handle.write_interrupt(ep, &framed, timeout) {
-                                    Ok(_) => return Ok(()),
-                                    Err(e2) => {
-                                        last_rusb = Some(e2);
-                                        let _ = self.handle.clear_halt(ep);
-                                        std::thread::sleep(Duration::from_millis(
-                                            20 * attempt as u64,
-                                        ));
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-
-                match self.handle.write_bulk(ep, data, timeout) {
-                    Ok(_) => return Ok(()),
+            // One attempt per call (bulk preferred, interrupt fallback);
+            // repeating a failed exchange is left to the device's
+            // RetryPolicy. A failure clears any halt/stall on the
+            // endpoint so that the retry can get through.
+            // If caller provided a PN532 host payload (TFI=0xD4), send a
+            // framed host packet; fall back to raw if it cannot be framed.
+            let framed = if data.first() == Some(&crate::constants::PN532_CMD_PREFIX_HOST) {
+                Frame::encode(data).ok()
+            } else {
+                None
+            };
+            let data = framed.as_deref().unwrap_or(data);
+
+            return match self.handle.write_bulk(ep, data, timeout) {
+                Ok(_) => Ok(()),
+                Err(_) => match self.handle.write_interrupt(ep, data, timeout) {
+                    Ok(_) => Ok(()),
                     Err(e) => {
-                        last_rusb = Some(e);
-                        match self.handle.write_interrupt(ep, data, timeout) {
-                            Ok(_) => return Ok(()),
-                            Err(e2) => {
-                                last_rusb = Some(e2);
-                                let _ = self.handle.clear_halt(ep);
-                                std::thread::sleep(Duration::from_millis(20 * attempt as u64));
-                                continue;
-                            }
-                        }
+                        let _ = self.handle.clear_halt(ep);
+                        Err(e.into())
                     }
-                }
-            }
-            if let Some(e) = last_rusb {
-                return Err(e.into());
-            }
-            return Err(Error::Timeout);
+                },
+            };
         }
 
         // Fallback to a vendor-specific control transfer if no OUT endpoint
@@ -154,65 +130,51 @@ impl Transport for UsbTransport {
         let mut buf = vec![0u8; 512];
 
         if let Some(ep) = self.in_ep {
-            // Attempt multiple attempts to read from the IN endpoint
-            // to tolerate transient IO errors or endpoint stalls. On
-            // failure try clearing the halt and retry with a small
-            // backoff. If an ACK-only PN532 frame is observed, attempt
-            // a follow-up read but treat a follow-up failure as
-            // non-fatal (return the ACK bytes) so callers can still
-            // examine the transport buffer.
-            let mut last_err: Option<rusb::Error> = None;
+            // One attempt per call, like `send`: the bulk read falls
+            // back to the interrupt endpoint, and a failure clears any
+            // halt/stall and is returned for the device's RetryPolicy.
+            // If an ACK-only PN532 frame is observed, attempt a follow-up
+            // read but treat a follow-up failure as non-fatal (return the
+            // ACK bytes) so callers can still examine the transport
+            // buffer.
             let pn532_ack = [0x00u8, 0x00u8, 0xFFu8, 0x00u8, 0xFFu8, 0x00u8];
-            for attempt in 1..=3 {
-                match self.handle.read_bulk(ep, &mut buf, timeout) {
-                    Ok(n) => {
-                        buf.truncate(n);
-                        // If ACK-only, try a short follow-up read and
-                        // append bytes if present; on follow-up error,
-                        // still return the ACK bytes.
-                        if self.device_type == DeviceType::S330 && buf == pn532_ack {
-                            let mut follow = vec![0u8; 512];
-                            match self.handle.read_bulk(ep, &mut follow, timeout) {
-                                Ok(n2) => {
-                                    follow.truncate(n2);
-                                    buf.extend_from_slice(&follow);
-                                }
-                                Err(_) => {
-                                    // Best-effort: try interrupt endpoint as a
-                                    // last-ditch follow-up. Ignore follow-up
-                                    // failures as callers may already have
-                                    // useful ACK data.
-                                    let _ = self.handle.read_interrupt(ep, &mut follow, timeout);
-                                }
-                            }
-                        }
-                        return Ok(buf);
-                    }
-                    Err(e) => {
-                        last_err = Some(e);
-                        // Try reading via interrupt endpoint as a
-                        // fallback before attempting to recover the
-                        // bulk endpoint.
-                        match self.handle.read_interrupt(ep, &mut buf, timeout) {
-                            Ok(n) => {
-                                buf.truncate(n);
-                                return Ok(buf);
+            return match self.handle.read_bulk(ep, &mut buf, timeout) {
+                Ok(n) => {
+                    buf.truncate(n);
+                    // If ACK-only, try a short follow-up read and append
+                    // bytes if present; on follow-up error, still return
+                    // the ACK bytes.
+                    if matches!(self.device_type, DeviceType::S330 | DeviceType::S380)
+                        && buf == pn532_ack
+                    {
+                        let mut follow = vec![0u8; 512];
+                        match self.handle.read_bulk(ep, &mut follow, timeout) {
+                            Ok(n2) => {
+                                follow.truncate(n2);
+                                buf.extend_from_slice(&follow);
                             }
                             Err(_) => {
-                                // Clear a possible halt/stall and retry.
-                                let _ = self.handle.clear_halt(ep);
-                                std::thread::sleep(Duration::from_millis(20 * attempt as u64));
-                                continue;
+                                // Best-effort: try interrupt endpoint as a
+                                // last-ditch follow-up. Ignore follow-up
+                                // failures as callers may already have
+                                // useful ACK data.
+                                let _ = self.handle.read_interrupt(ep, &mut follow, timeout);
                             }
                         }
                     }
+                    Ok(buf)
                 }
-            }
-            // All attempts failed: return the last rusb error if present
-            if let Some(e) = last_err {
-                return Err(e.into());
-            }
-            return Err(Error::Timeout);
+                Err(e) => match self.handle.read_interrupt(ep, &mut buf, timeout) {
+                    Ok(n) => {
+                        buf.truncate(n);
+                        Ok(buf)
+                    }
+                    Err(_) => {
+                        let _ = self.handle.clear_halt(ep);
+                        Err(e.into())
+                    }
+                },
+            };
         }
 
         // No IN endpoint — try a control read (rare for PaSoRi but keep a fallback)
@@ -373,6 +335,7 @@ mod tests {
                     crate::types::DeviceType::S310
                         | crate::types::DeviceType::S320
                         | crate::types::DeviceType::S330
+                        | crate::types::DeviceType::S380
                 ));
             }
             Err(e) => {
@@ -2,6 +2,7 @@
 
 use crate::Error;
 use std::convert::TryFrom;
+use std::time::Duration;
 
 /// IDm - Newtype Pattern (8 バイト)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -49,6 +50,148 @@ impl Pmm {
     pub fn as_bytes(&self) -> &[u8; 8] {
         &self.0
     }
+
+    /// ROM type (byte 0)
+    pub fn rom_type(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// IC type (byte 1)
+    pub fn ic_type(&self) -> u8 {
+        self.0[1]
+    }
+
+    /// Chip family identified from the IC type byte
+    pub fn chip_kind(&self) -> ChipKind {
+        match self.ic_type() {
+            0x00..=0x02 | 0x08 | 0x09 | 0x0b..=0x0d | 0x20 | 0x31..=0x36 => ChipKind::Standard,
+            0x06 | 0x07 | 0x10..=0x1f => ChipKind::MobileFelica,
+            0xe0 | 0xe1 => ChipKind::Plug,
+            0xf0 => ChipKind::Lite,
+            0xf1 | 0xf2 => ChipKind::LiteS,
+            ic_type => ChipKind::Unknown {
+                rom_type: self.rom_type(),
+                ic_type,
+            },
+        }
+    }
+
+    /// Maximum response time parameters for a command class (bytes 2-7)
+    pub fn response_time_params(&self, class: CommandClass) -> ResponseTimeParams {
+        let idx = match class {
+            CommandClass::RequestService => 2,
+            CommandClass::RequestResponse => 3,
+            CommandClass::Authentication => 4,
+            CommandClass::Read => 5,
+            CommandClass::Write => 6,
+            CommandClass::Other => 7,
+        };
+        ResponseTimeParams::from_byte(self.0[idx])
+    }
+
+    /// Maximum response time of a command of `class` addressing `n`
+    /// blocks or nodes.
+    pub fn max_response_time(&self, class: CommandClass, n: usize) -> Duration {
+        self.response_time_params(class).max_response_time(n)
+    }
+}
+
+/// FeliCa chip family, as identified by the PMm IC type byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChipKind {
+    /// FeliCa Standard (RC-S830/S915/S919/S95x/S962/SA0x series)
+    Standard,
+    /// FeliCa Lite (RC-S965)
+    Lite,
+    /// FeliCa Lite-S (RC-S966/S967)
+    LiteS,
+    /// Mobile FeliCa IC chip (all generations)
+    MobileFelica,
+    /// FeliCa Plug (RC-S926, RC-S967 in plug mode)
+    Plug,
+    /// IC type not known to this library
+    Unknown {
+        /// PMm byte 0
+        rom_type: u8,
+        /// PMm byte 1
+        ic_type: u8,
+    },
+}
+
+impl ChipKind {
+    /// Largest number of blocks the chip accepts in one Read Without
+    /// Encryption command. Unknown chips start from the frame-size limit.
+    pub fn max_read_blocks(&self) -> usize {
+        match self {
+            Self::Lite | Self::LiteS => 4,
+            Self::Plug => 12,
+            Self::Standard | Self::MobileFelica | Self::Unknown { .. } => 15,
+        }
+    }
+
+    /// Largest number of blocks the chip accepts in one Write Without
+    /// Encryption command.
+    pub fn max_write_blocks(&self) -> usize {
+        match self {
+            Self::Lite | Self::LiteS => 1,
+            Self::Plug => 8,
+            Self::Standard | Self::MobileFelica | Self::Unknown { .. } => 11,
+        }
+    }
+}
+
+/// Command classes whose maximum response time is encoded in the PMm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandClass {
+    /// Request Service (variable: number of nodes)
+    RequestService,
+    /// Request Response (fixed)
+    RequestResponse,
+    /// Authentication (variable)
+    Authentication,
+    /// Read (variable: number of blocks)
+    Read,
+    /// Write (variable: number of blocks)
+    Write,
+    /// Other commands such as Search Service Code (fixed)
+    Other,
+}
+
+/// Maximum response time parameters of one PMm byte.
+///
+/// Byte layout: E (bits 7-6), B (bits 5-3), A (bits 2-0). The maximum
+/// response time is `T × ((B + 1) × n + (A + 1)) × 4^E` with
+/// `T = 256 × 16 / fc` (about 0.302 ms) and `n` the number of blocks or
+/// nodes; fixed-time commands use `n = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseTimeParams {
+    /// Fixed part (bits 2-0)
+    pub a: u8,
+    /// Per block/node part (bits 5-3)
+    pub b: u8,
+    /// Exponent (bits 7-6)
+    pub e: u8,
+}
+
+impl ResponseTimeParams {
+    /// Carrier frequency fc in Hz
+    const CARRIER_HZ: u128 = 13_560_000;
+
+    /// Split a PMm byte into its parameters
+    pub fn from_byte(byte: u8) -> Self {
+        Self {
+            a: byte & 0x07,
+            b: (byte >> 3) & 0x07,
+            e: byte >> 6,
+        }
+    }
+
+    /// Maximum response time for a command addressing `n` blocks or nodes
+    pub fn max_response_time(&self, n: usize) -> Duration {
+        let units = ((self.b as u128 + 1) * n as u128 + (self.a as u128 + 1)) << (2 * self.e);
+        let nanos = 256 * 16 * units * 1_000_000_000 / Self::CARRIER_HZ;
+        Duration::from_nanos(nanos as u64)
+    }
 }
 
 impl TryFrom<&[u8]> for Pmm {
@@ -93,21 +236,283 @@ impl SystemCode {
     }
 }
 
+/// Request code of a Polling command, selecting the Request Data the card
+/// appends to its response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PollingRequest {
+    /// No Request Data (request code 0x00)
+    #[default]
+    NoRequest,
+    /// System code (request code 0x01)
+    SystemCode,
+    /// Communication performance (request code 0x02)
+    CommunicationPerformance,
+}
+
+impl PollingRequest {
+    /// Request code byte sent in the Polling command
+    pub fn request_code(self) -> u8 {
+        match self {
+            Self::NoRequest => 0x00,
+            Self::SystemCode => 0x01,
+            Self::CommunicationPerformance => 0x02,
+        }
+    }
+
+    /// Interpret the raw Request Data of a Polling response for this request.
+    pub fn decode_request_data(self, data: [u8; 2]) -> Option<PollingRequestData> {
+        match self {
+            Self::NoRequest => None,
+            Self::SystemCode => Some(PollingRequestData::SystemCode(SystemCode::from_le_bytes(
+                data,
+            ))),
+            // The first byte is reserved; the flags live in the second one.
+            Self::CommunicationPerformance => Some(PollingRequestData::CommunicationPerformance(
+                CommunicationPerformance::from_bits(data[1]),
+            )),
+        }
+    }
+}
+
+/// Number of time slots a card may answer a Polling command in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeSlots {
+    /// Single slot (time slot byte 0x00)
+    #[default]
+    One,
+    /// 2 slots (0x01)
+    Two,
+    /// 4 slots (0x03)
+    Four,
+    /// 8 slots (0x07)
+    Eight,
+    /// 16 slots (0x0F)
+    Sixteen,
+}
+
+impl TimeSlots {
+    /// Number of slots
+    pub fn count(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+            Self::Four => 4,
+            Self::Eight => 8,
+            Self::Sixteen => 16,
+        }
+    }
+
+    /// Time slot byte sent in the Polling command (slot count - 1)
+    pub fn time_slot_byte(self) -> u8 {
+        self.count() - 1
+    }
+
+    /// Time by which the response in the last slot has started: the
+    /// first slot opens 2.417 ms after the command and each slot lasts
+    /// 1.208 ms.
+    pub fn max_response_time(self) -> Duration {
+        Duration::from_micros(2_417 + 1_208 * self.count() as u64)
+    }
+
+    /// Smallest slot count that gives `max_targets` cards a slot each,
+    /// capped at 16.
+    pub fn for_targets(max_targets: u8) -> Self {
+        match max_targets {
+            0 | 1 => Self::One,
+            2 => Self::Two,
+            3 | 4 => Self::Four,
+            5..=8 => Self::Eight,
+            _ => Self::Sixteen,
+        }
+    }
+}
+
+/// Decoded Request Data of a Polling response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollingRequestData {
+    /// System code of the responding system (request code 0x01)
+    SystemCode(SystemCode),
+    /// Supported bitrates (request code 0x02)
+    CommunicationPerformance(CommunicationPerformance),
+}
+
+/// Communication performance flags reported for request code 0x02
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommunicationPerformance(u8);
+
+impl CommunicationPerformance {
+    const BITRATE_212K: u8 = 0x01;
+    const BITRATE_424K: u8 = 0x02;
+    const BITRATE_848K: u8 = 0x04;
+    const BITRATE_1M6: u8 = 0x08;
+    const AUTOMATIC_DETECTION: u8 = 0x80;
+
+    /// Wrap the raw flag byte
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Raw flag byte
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Whether the card supports 212 kbps
+    pub fn supports_212kbps(&self) -> bool {
+        self.0 & Self::BITRATE_212K != 0
+    }
+
+    /// Whether the card supports 424 kbps
+    pub fn supports_424kbps(&self) -> bool {
+        self.0 & Self::BITRATE_424K != 0
+    }
+
+    /// Whether the card supports 848 kbps
+    pub fn supports_848kbps(&self) -> bool {
+        self.0 & Self::BITRATE_848K != 0
+    }
+
+    /// Whether the card supports 1.6 Mbps
+    pub fn supports_1_6mbps(&self) -> bool {
+        self.0 & Self::BITRATE_1M6 != 0
+    }
+
+    /// Whether the card supports automatic bitrate detection
+    pub fn automatic_detection(&self) -> bool {
+        self.0 & Self::AUTOMATIC_DETECTION != 0
+    }
+}
+
 /// ServiceCode (u16)
+///
+/// Bits 15-6 hold the service number and bits 5-0 the attribute, which
+/// encodes the service type, the access rights and whether the service
+/// can be accessed without authentication (bit 0 set).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ServiceCode(u16);
 
+/// Service type encoded in the service code attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServiceType {
+    /// Random service: blocks addressed directly by number
+    Random,
+    /// Cyclic service: ring buffer of records, block 0 is the newest
+    Cyclic,
+    /// Purse service: a single block holding a balance
+    Purse,
+}
+
+/// Access rights encoded in the service code attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServiceAccess {
+    /// Read/write (direct access for purse services)
+    ReadWrite,
+    /// Read-only
+    ReadOnly,
+    /// Purse cashback or decrement access
+    CashbackDecrement,
+    /// Purse decrement access
+    Decrement,
+}
+
 impl ServiceCode {
+    /// Largest service number (10 bits).
+    pub const MAX_NUMBER: u16 = 0x03ff;
+
     pub const fn new(code: u16) -> Self {
         Self(code)
     }
 
+    /// Build a service code from its number and typed attributes. Fails
+    /// when the number does not fit in 10 bits or when the access mode is
+    /// not defined for the service type (e.g. decrement on a random service).
+    pub fn from_attributes(
+        number: u16,
+        service_type: ServiceType,
+        access: ServiceAccess,
+        requires_authentication: bool,
+    ) -> Result<Self, Error> {
+        if number > Self::MAX_NUMBER {
+            return Err(Error::UnsupportedOperation(format!(
+                "service number {number:#06x} does not fit in 10 bits"
+            )));
+        }
+
+        let attribute = match (service_type, access) {
+            (ServiceType::Random, ServiceAccess::ReadWrite) => 0b00_1000,
+            (ServiceType::Random, ServiceAccess::ReadOnly) => 0b00_1010,
+            (ServiceType::Cyclic, ServiceAccess::ReadWrite) => 0b00_1100,
+            (ServiceType::Cyclic, ServiceAccess::ReadOnly) => 0b00_1110,
+            (ServiceType::Purse, ServiceAccess::ReadWrite) => 0b01_0000,
+            (ServiceType::Purse, ServiceAccess::CashbackDecrement) => 0b01_0010,
+            (ServiceType::Purse, ServiceAccess::Decrement) => 0b01_0100,
+            (ServiceType::Purse, ServiceAccess::ReadOnly) => 0b01_0110,
+            (ty, acc) => {
+                return Err(Error::UnsupportedOperation(format!(
+                    "{acc:?} access is not defined for {ty:?} services"
+                )));
+            }
+        };
+        let no_auth = if requires_authentication { 0 } else { 1 };
+
+        Ok(Self((number << 6) | attribute | no_auth))
+    }
+
     pub fn as_u16(&self) -> u16 {
         self.0
     }
     pub fn to_le_bytes(&self) -> [u8; 2] {
         self.0.to_le_bytes()
     }
+
+    /// Service number (bits 15-6).
+    pub fn number(&self) -> u16 {
+        self.0 >> 6
+    }
+
+    /// Raw 6-bit attribute (bits 5-0).
+    pub fn attribute(&self) -> u8 {
+        (self.0 & 0x3f) as u8
+    }
+
+    /// Service type, or `None` when the attribute is not a service
+    /// attribute (area codes and reserved values).
+    pub fn service_type(&self) -> Option<ServiceType> {
+        match self.attribute() {
+            0x08..=0x0b => Some(ServiceType::Random),
+            0x0c..=0x0f => Some(ServiceType::Cyclic),
+            0x10..=0x17 => Some(ServiceType::Purse),
+            _ => None,
+        }
+    }
+
+    /// Access rights, or `None` when the attribute is not a service attribute.
+    pub fn access(&self) -> Option<ServiceAccess> {
+        let attr = self.attribute();
+        match self.service_type()? {
+            ServiceType::Random | ServiceType::Cyclic if attr & 0x02 == 0 => {
+                Some(ServiceAccess::ReadWrite)
+            }
+            ServiceType::Random | ServiceType::Cyclic => Some(ServiceAccess::ReadOnly),
+            ServiceType::Purse => Some(match (attr >> 1) & 0x03 {
+                0 => ServiceAccess::ReadWrite,
+                1 => ServiceAccess::CashbackDecrement,
+                2 => ServiceAccess::Decrement,
+                _ => ServiceAccess::ReadOnly,
+            }),
+        }
+    }
+
+    /// Whether the service only accepts authenticated (encrypted) access.
+    /// Always `false` for codes that are not service codes.
+    pub fn requires_authentication(&self) -> bool {
+        self.service_type().is_some() && self.0 & 0x01 == 0
+    }
+
+    /// Whether the service is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.access() == Some(ServiceAccess::ReadOnly)
+    }
 }
 
 /// BlockData (16 バイト)
@@ -146,6 +551,7 @@ pub enum DeviceType {
     S310,
     S320,
     S330,
+    S380,
 }
 
 impl DeviceType {
@@ -154,6 +560,8 @@ impl DeviceType {
             0x006c => Some(Self::S310),
             0x01bb => Some(Self::S320),
             0x02e1 => Some(Self::S330),
+            // RC-S380/S and RC-S380/P
+            0x06c1 | 0x06c3 => Some(Self::S380),
             _ => None,
         }
     }
@@ -166,13 +574,36 @@ impl Default for DeviceType {
     }
 }
 
-/// AccessMode
+/// Access mode field (3 bits) of a block list element.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AccessMode {
-    CashBackOrDecrement = 0,
-    DirectAccessOrDecrement = 1,
-    DirectAccessOrRead = 2,
+    /// Reads, direct-access writes and purse decrement writes (000b).
+    Normal = 0b000,
+    /// Purse cashback access (001b).
+    Cashback = 0b001,
+}
+
+impl AccessMode {
+    /// The 3-bit field value sent in a block list element.
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for AccessMode {
+    type Error = Error;
+
+    /// Parse the 3-bit access mode field of a block list element.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0b000 => Ok(Self::Normal),
+            0b001 => Ok(Self::Cashback),
+            other => Err(Error::FrameFormat(format!(
+                "invalid block access mode: {other:#03b}"
+            ))),
+        }
+    }
 }
 
 /// BlockElement
@@ -184,6 +615,9 @@ pub struct BlockElement {
 }
 
 impl BlockElement {
+    /// Length bit of the first element byte: set for the 2-byte form.
+    const LENGTH_BIT: u8 = 0x80;
+
     pub fn new(service_index: u8, access_mode: AccessMode, block_number: u16) -> Self {
         Self {
             service_index,
@@ -192,13 +626,56 @@ impl BlockElement {
         }
     }
 
-    /// FeliCa のブロック要素を 3 バイトにエンコードする
-    pub fn encode(&self) -> [u8; 3] {
-        [
-            self.service_index,
-            self.access_mode as u8,
-            (self.block_number & 0xff) as u8,
-        ]
+    /// Whether this element needs the 3-byte form (block number > 255).
+    pub fn is_three_byte(&self) -> bool {
+        self.block_number > 0xff
+    }
+
+    /// Number of bytes produced by [`BlockElement::encode`] (2 or 3).
+    pub fn encoded_len(&self) -> usize {
+        if self.is_three_byte() { 3 } else { 2 }
+    }
+
+    /// FeliCa のブロックリスト要素をエンコードする
+    ///
+    /// The first byte carries the length bit (bit 7), the access mode
+    /// (bits 6-4) and the service code list order (bits 3-0). Block
+    /// numbers up to 255 use the 2-byte form (length bit set, 1-byte
+    /// block number); larger numbers use the 3-byte form (length bit
+    /// cleared, 16-bit little-endian block number).
+    pub fn encode(&self) -> Vec<u8> {
+        let head = (self.access_mode.bits() << 4) | (self.service_index & 0x0f);
+        if self.is_three_byte() {
+            let [lo, hi] = self.block_number.to_le_bytes();
+            vec![head, lo, hi]
+        } else {
+            vec![Self::LENGTH_BIT | head, (self.block_number & 0xff) as u8]
+        }
+    }
+
+    /// Decode a single block list element from the start of `bytes`.
+    /// Returns the element together with the number of bytes consumed.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        let head = *bytes.first().ok_or(Error::InvalidLength {
+            expected: 2,
+            actual: 0,
+        })?;
+        let len = if head & Self::LENGTH_BIT != 0 { 2 } else { 3 };
+        if bytes.len() < len {
+            return Err(Error::InvalidLength {
+                expected: len,
+                actual: bytes.len(),
+            });
+        }
+
+        let access_mode = AccessMode::try_from((head >> 4) & 0x07)?;
+        let block_number = if len == 2 {
+            bytes[1] as u16
+        } else {
+            u16::from_le_bytes([bytes[1], bytes[2]])
+        };
+
+        Ok((Self::new(head & 0x0f, access_mode, block_number), len))
     }
 }
 
@@ -219,10 +696,87 @@ mod tests {
         assert!(Idm::try_from(&b[..]).is_err());
     }
 
+    #[test]
+    fn polling_request_decodes_request_data() {
+        assert_eq!(
+            PollingRequest::NoRequest.decode_request_data([0x03, 0x00]),
+            None
+        );
+        assert_eq!(
+            PollingRequest::SystemCode.decode_request_data([0x03, 0x00]),
+            Some(PollingRequestData::SystemCode(SystemCode::SUICA))
+        );
+
+        match PollingRequest::CommunicationPerformance.decode_request_data([0x00, 0x83]) {
+            Some(PollingRequestData::CommunicationPerformance(perf)) => {
+                assert!(perf.supports_212kbps());
+                assert!(perf.supports_424kbps());
+                assert!(!perf.supports_848kbps());
+                assert!(!perf.supports_1_6mbps());
+                assert!(perf.automatic_detection());
+            }
+            other => panic!("expected communication performance, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn pmm_chip_kind() {
+        let kind = |ic: u8| Pmm::from_bytes([0x10, ic, 0, 0, 0, 0, 0, 0]).chip_kind();
+        assert_eq!(kind(0x01), ChipKind::Standard);
+        assert_eq!(kind(0x32), ChipKind::Standard);
+        assert_eq!(kind(0x13), ChipKind::MobileFelica);
+        assert_eq!(kind(0xf0), ChipKind::Lite);
+        assert_eq!(kind(0xf1), ChipKind::LiteS);
+        assert_eq!(kind(0xe0), ChipKind::Plug);
+        assert_eq!(
+            kind(0x7a),
+            ChipKind::Unknown {
+                rom_type: 0x10,
+                ic_type: 0x7a
+            }
+        );
+    }
+
+    #[test]
+    fn pmm_response_time_params() {
+        // E=2, B=1, A=3 in the Read byte; all others zero
+        let pmm = Pmm::from_bytes([0x01, 0x20, 0, 0, 0, 0x8B, 0, 0xFF]);
+        let p = pmm.response_time_params(CommandClass::Read);
+        assert_eq!((p.a, p.b, p.e), (3, 1, 2));
+
+        // T × ((1 + 1) × 4 + (3 + 1)) × 4^2 = T × 192 ≈ 58.0 ms
+        let t = pmm.max_response_time(CommandClass::Read, 4);
+        assert_eq!(t.as_micros(), 57_996);
+
+        // Zero byte: T × 1 ≈ 0.302 ms regardless of n for A=B=0 and n=0
+        let t = pmm.max_response_time(CommandClass::Write, 0);
+        assert_eq!(t.as_micros(), 302);
+
+        // E=3, B=7, A=7
+        let t = pmm.max_response_time(CommandClass::Other, 0);
+        assert_eq!(t.as_micros(), 154_657);
+    }
+
+    #[test]
+    fn time_slots_for_targets() {
+        assert_eq!(TimeSlots::for_targets(0), TimeSlots::One);
+        assert_eq!(TimeSlots::for_targets(3), TimeSlots::Four);
+        assert_eq!(TimeSlots::for_targets(200), TimeSlots::Sixteen);
+        assert_eq!(TimeSlots::Sixteen.time_slot_byte(), 0x0F);
+        assert_eq!(TimeSlots::One.time_slot_byte(), 0x00);
+    }
+
     #[test]
     fn block_element_encode_ok() {
-        let be = BlockElement::new(1, AccessMode::DirectAccessOrRead, 0x1234);
-        assert_eq!(be.encode(), [1, 2, 0x34]);
+        let be = BlockElement::new(1, AccessMode::Normal, 0x0034);
+        assert_eq!(be.encode(), [0x81, 0x34]);
+
+        let be = BlockElement::new(1, AccessMode::Normal, 0x1234);
+        assert_eq!(be.encode(), [0x01, 0x34, 0x12]);
+
+        // Cashback access uses access mode 001b
+        let be = BlockElement::new(0, AccessMode::Cashback, 0x0005);
+        assert_eq!(be.encode(), [0x90, 0x05]);
     }
 
     #[test]
@@ -244,24 +798,131 @@ mod tests {
         assert_eq!(svc.to_le_bytes(), 0x090f_u16.to_le_bytes());
     }
 
+    #[test]
+    fn service_code_attribute_accessors() {
+        // 0x090f: number 0x24, cyclic read-only without authentication
+        let svc = ServiceCode::new(0x090f);
+        assert_eq!(svc.number(), 0x24);
+        assert_eq!(svc.attribute(), 0x0f);
+        assert_eq!(svc.service_type(), Some(ServiceType::Cyclic));
+        assert_eq!(svc.access(), Some(ServiceAccess::ReadOnly));
+        assert!(!svc.requires_authentication());
+        assert!(svc.is_read_only());
+
+        // 0x1008: random read/write, authentication required
+        let svc = ServiceCode::new(0x1008);
+        assert_eq!(svc.service_type(), Some(ServiceType::Random));
+        assert_eq!(svc.access(), Some(ServiceAccess::ReadWrite));
+        assert!(svc.requires_authentication());
+
+        // 0x1015: purse decrement without authentication
+        let svc = ServiceCode::new(0x1015);
+        assert_eq!(svc.service_type(), Some(ServiceType::Purse));
+        assert_eq!(svc.access(), Some(ServiceAccess::Decrement));
+
+        // Area codes carry no service attributes
+        let area = ServiceCode::new(0x1000);
+        assert_eq!(area.service_type(), None);
+        assert_eq!(area.access(), None);
+        assert!(!area.requires_authentication());
+    }
+
+    #[test]
+    fn service_code_from_attributes_roundtrip() {
+        let cases = [
+            (ServiceType::Random, ServiceAccess::ReadWrite),
+            (ServiceType::Random, ServiceAccess::ReadOnly),
+            (ServiceType::Cyclic, ServiceAccess::ReadWrite),
+            (ServiceType::Cyclic, ServiceAccess::ReadOnly),
+            (ServiceType::Purse, ServiceAccess::ReadWrite),
+            (ServiceType::Purse, ServiceAccess::CashbackDecrement),
+            (ServiceType::Purse, ServiceAccess::Decrement),
+            (ServiceType::Purse, ServiceAccess::ReadOnly),
+        ];
+        for &(ty, acc) in &cases {
+            for &auth in &[true, false] {
+                let svc = ServiceCode::from_attributes(0x123, ty, acc, auth).unwrap();
+                assert_eq!(svc.number(), 0x123);
+                assert_eq!(svc.service_type(), Some(ty));
+                assert_eq!(svc.access(), Some(acc));
+                assert_eq!(svc.requires_authentication(), auth);
+            }
+        }
+
+        assert_eq!(
+            ServiceCode::from_attributes(0x24, ServiceType::Cyclic, ServiceAccess::ReadOnly, false)
+                .unwrap(),
+            ServiceCode::new(0x090f)
+        );
+    }
+
+    #[test]
+    fn service_code_from_attributes_rejects_invalid() {
+        assert!(
+            ServiceCode::from_attributes(0, ServiceType::Random, ServiceAccess::Decrement, false)
+                .is_err()
+        );
+        assert!(
+            ServiceCode::from_attributes(
+                0x400,
+                ServiceType::Random,
+                ServiceAccess::ReadOnly,
+                false
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn device_type_from_pid() {
         assert_eq!(DeviceType::from_product_id(0x006c), Some(DeviceType::S310));
         assert_eq!(DeviceType::from_product_id(0x01bb), Some(DeviceType::S320));
         assert_eq!(DeviceType::from_product_id(0x02e1), Some(DeviceType::S330));
+        assert_eq!(DeviceType::from_product_id(0x06c1), Some(DeviceType::S380));
+        assert_eq!(DeviceType::from_product_id(0x06c3), Some(DeviceType::S380));
         assert_eq!(DeviceType::from_product_id(0x9999), None);
     }
 
     #[test]
     fn access_mode_repr_and_block_element_bounds() {
-        // Ensure AccessMode discriminants match spec
-        assert_eq!(AccessMode::CashBackOrDecrement as u8, 0);
-        assert_eq!(AccessMode::DirectAccessOrDecrement as u8, 1);
-        assert_eq!(AccessMode::DirectAccessOrRead as u8, 2);
-
-        // BlockElement encodes only low byte of block number per FeliCa spec
-        let be = BlockElement::new(2, AccessMode::CashBackOrDecrement, 0x01FF);
-        assert_eq!(be.encode(), [2, 0, 0xFF]);
+        // AccessMode discriminants are the spec's 3-bit field values
+        assert_eq!(AccessMode::Normal as u8, 0b000);
+        assert_eq!(AccessMode::Cashback as u8, 0b001);
+
+        // 255 is the last block number that fits the 2-byte form; 256
+        // switches to the 3-byte form with a 16-bit LE block number.
+        let be = BlockElement::new(2, AccessMode::Cashback, 0x00FF);
+        assert!(!be.is_three_byte());
+        assert_eq!(be.encode(), [0x92, 0xFF]);
+
+        let be = BlockElement::new(2, AccessMode::Cashback, 0x0100);
+        assert!(be.is_three_byte());
+        assert_eq!(be.encode(), [0x12, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn block_element_roundtrip_both_forms() {
+        for mode in [AccessMode::Normal, AccessMode::Cashback] {
+            for &block in &[0u16, 1, 0x7f, 0xff, 0x100, 0x1234, 0xffff] {
+                let be = BlockElement::new(0x0f, mode, block);
+                let bytes = be.encode();
+                assert_eq!(bytes.len(), be.encoded_len());
+                // Access mode field sits in bits 6-4 of the first byte
+                assert_eq!((bytes[0] >> 4) & 0x07, mode.bits());
+                let (decoded, used) = BlockElement::decode(&bytes).unwrap();
+                assert_eq!(decoded, be);
+                assert_eq!(used, bytes.len());
+            }
+        }
+    }
+
+    #[test]
+    fn block_element_decode_errors() {
+        assert!(BlockElement::decode(&[]).is_err());
+        // 3-byte form (length bit cleared) truncated after two bytes
+        assert!(BlockElement::decode(&[0x00, 0x01]).is_err());
+        // Access mode 0b111 is not defined
+        assert!(BlockElement::decode(&[0xF0, 0x01]).is_err());
     }
 
     #[test]
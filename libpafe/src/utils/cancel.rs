@@ -0,0 +1,66 @@
+//! Cancellation token shared between a blocking operation and the thread
+//! that may want to stop it.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Cloneable flag that, once fired, makes blocking waits such as
+/// `Device::wait_for_card` return `Error::Cancelled`. Clones share state.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancelToken {
+    /// A token that has not fired.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fire the token and wake every thread sleeping on it.
+    pub fn cancel(&self) {
+        let (flag, cvar) = &*self.inner;
+        *flag.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        cvar.notify_all();
+    }
+
+    /// Whether the token has fired.
+    pub fn is_cancelled(&self) -> bool {
+        *self.inner.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sleep for `duration` or until the token fires, whichever comes
+    /// first. Returns `true` when the token has fired.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (flag, cvar) = &*self.inner;
+        let guard = flag.lock().unwrap_or_else(|e| e.into_inner());
+        let (guard, _) = cvar
+            .wait_timeout_while(guard, duration, |cancelled| !*cancelled)
+            .unwrap_or_else(|e| e.into_inner());
+        *guard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn cancel_wakes_sleeping_thread() {
+        let token = CancelToken::new();
+        let other = token.clone();
+        let start = Instant::now();
+        let handle = std::thread::spawn(move || other.sleep(Duration::from_secs(10)));
+        std::thread::sleep(Duration::from_millis(20));
+        token.cancel();
+        assert!(handle.join().unwrap());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn sleep_without_cancel_times_out() {
+        assert!(!CancelToken::new().sleep(Duration::from_millis(1)));
+    }
+}
@@ -10,6 +10,16 @@ use std::time::Duration;
 /// doesn't provide an explicit timeout.
 pub const DEFAULT_READ_TIMEOUT_MS: u64 = 1000;
 
+/// Time allowed on top of a card's maximum response time to cover reader
+/// and USB latency.
+pub const RESPONSE_TIME_MARGIN_MS: u64 = 100;
+
+/// Receive timeout in milliseconds for a command whose card-side response
+/// time is at most `max_response`.
+pub fn response_timeout_ms(max_response: Duration) -> u64 {
+    max_response.as_micros().div_ceil(1000) as u64 + RESPONSE_TIME_MARGIN_MS
+}
+
 /// Convert milliseconds to Duration.
 pub fn ms(ms: u64) -> Duration {
     Duration::from_millis(ms)
@@ -29,6 +39,12 @@ mod tests {
         assert_eq!(ms(500).as_millis(), 500);
     }
 
+    #[test]
+    fn response_timeout_rounds_up_and_adds_margin() {
+        let t = response_timeout_ms(Duration::from_micros(1_001));
+        assert_eq!(t, 2 + RESPONSE_TIME_MARGIN_MS);
+    }
+
     #[test]
     fn default_timeout_positive() {
         assert!(default_read_timeout() >= ms(1));
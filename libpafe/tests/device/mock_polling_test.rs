@@ -12,6 +12,10 @@ fn initialized_mock_polling_returns_card() {
     let mut dev = common::helpers::initialized_mock_device(DeviceType::S320, responses).unwrap();
 
     let card = dev.polling(common::fixtures::sample_system_code()).unwrap();
-    assert_eq!(card.idm(), &common::fixtures::sample_idm());
-    assert_eq!(card.pmm(), &common::fixtures::sample_pmm());
+    assert_eq!(card.idm(), Some(&common::fixtures::sample_idm()));
+    assert_eq!(card.pmm(), Some(&common::fixtures::sample_pmm()));
+    assert_eq!(
+        card.system_code(),
+        Some(common::fixtures::sample_system_code())
+    );
 }
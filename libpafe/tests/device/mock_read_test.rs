@@ -20,7 +20,7 @@ fn read_single_block_via_mock_device() {
         .read_blocks(
             &mut dev,
             &[common::fixtures::sample_service_code()],
-            &[BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0000)],
+            &[BlockElement::new(0, AccessMode::Normal, 0x0000)],
         )
         .unwrap();
 
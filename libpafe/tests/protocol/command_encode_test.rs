@@ -13,11 +13,11 @@ fn polling_and_read_encode() {
     };
 
     assert_eq!(cmd.command_code(), 0x00);
-    assert_eq!(cmd.encode(), vec![0x00, 0x34, 0x12, 1, 0]);
+    assert_eq!(cmd.encode().unwrap(), vec![0x00, 0x34, 0x12, 1, 0]);
 
     let idm = common::fixtures::sample_idm();
     let svc = common::fixtures::sample_service_code();
-    let block = BlockElement::new(0, AccessMode::DirectAccessOrRead, 0x0012);
+    let block = BlockElement::new(0, AccessMode::Normal, 0x0012);
 
     let read_cmd = Command::ReadWithoutEncryption {
         idm,
@@ -25,7 +25,7 @@ fn polling_and_read_encode() {
         blocks: vec![block],
     };
 
-    let payload = read_cmd.encode();
+    let payload = read_cmd.encode().unwrap();
     // Basic sanity checks on the produced payload
     assert_eq!(payload[0], read_cmd.command_code());
     assert_eq!(&payload[1..9], idm.as_bytes());
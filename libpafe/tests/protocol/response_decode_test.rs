@@ -12,11 +12,14 @@ fn polling_response_decodes_to_polling_variant() {
         Response::Polling {
             idm,
             pmm,
-            system_code,
+            request_data,
         } => {
             assert_eq!(idm, common::fixtures::sample_idm());
             assert_eq!(pmm, common::fixtures::sample_pmm());
-            assert_eq!(system_code, common::fixtures::sample_system_code());
+            assert_eq!(
+                request_data,
+                Some(common::fixtures::sample_system_code().to_le_bytes())
+            );
         }
         other => panic!("expected polling response, got {:?}", other),
     }